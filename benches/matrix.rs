@@ -0,0 +1,65 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use ratatui::{backend::TestBackend, layout::Rect, Terminal};
+use rjmatrix::matrix::{
+    create_matrix, draw_layers, update_layers, Direction, Layer, LineState, State,
+};
+
+fn update_line(c: &mut Criterion) {
+    let state = State::default();
+    let mut group = c.benchmark_group("update_line");
+    for len in [30, 60, 120, 240] {
+        let mut line = LineState::new(len, &state, 1);
+        group.bench_with_input(BenchmarkId::from_parameter(len), &len, |b, _| {
+            b.iter(|| line.update_line(black_box(&state)))
+        });
+    }
+    group.finish();
+}
+
+fn tick(c: &mut Criterion) {
+    let state = State::default();
+    let mut layers = Layer::create_layers(1);
+    create_matrix(&mut layers, Rect::new(0, 0, 400, 120), &state);
+    let mut tick = 0;
+    c.bench_function("tick 400x120", |b| {
+        b.iter(|| {
+            update_layers(&mut layers, &state, tick);
+            tick += 1;
+        })
+    });
+}
+
+fn render(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render 400x120");
+    for direction in [
+        Direction::Down,
+        Direction::Up,
+        Direction::Left,
+        Direction::Right,
+    ] {
+        let name = format!("{direction:?}");
+        let state = State {
+            direction,
+            ..State::default()
+        };
+        let mut terminal = Terminal::new(TestBackend::new(400, 120)).unwrap();
+        let mut layers = Layer::create_layers(1);
+        let area = Rect::new(0, 0, 400, 120);
+        create_matrix(&mut layers, area, &state);
+        // Fill the screen before timing so there is something to draw
+        for tick in 0..200 {
+            update_layers(&mut layers, &state, tick);
+        }
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                terminal
+                    .draw(|frame| draw_layers(frame, area, &layers, &state))
+                    .unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, update_line, tick, render);
+criterion_main!(benches);
@@ -0,0 +1,74 @@
+use crate::matrix::{create_matrix, draw_layers, update_layers, Layer, State};
+use ratatui::{backend::TestBackend, layout::Rect, Terminal};
+use std::io::{Error, ErrorKind, Result};
+use std::time::{Duration, Instant};
+
+// Parse a size like `200x60` into a width and height
+pub fn parse_size(size: &str) -> Result<(u16, u16)> {
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("size `{size}` should be WxH, e.g. 200x60"),
+        )
+    };
+    let (width, height) = size.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width: u16 = width.trim().parse().map_err(|_| invalid())?;
+    let height: u16 = height.trim().parse().map_err(|_| invalid())?;
+    // An empty buffer has nothing to draw
+    if width == 0 || height == 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "size should be at least 1x1",
+        ));
+    }
+    Ok((width, height))
+}
+
+// Time updating and drawing the matrix into an off screen buffer, which
+// leaves out how fast the terminal itself is
+pub fn run(
+    width: u16,
+    height: u16,
+    frames: u64,
+    layers: &mut Vec<Layer>,
+    state: &State,
+) -> Result<()> {
+    // Nothing to time, and the rates would come out as NaN
+    if frames == 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "frames should be at least 1",
+        ));
+    }
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    let area = Rect::new(0, 0, width, height);
+    create_matrix(layers, area, state);
+
+    let start = Instant::now();
+    for tick in 0..frames {
+        update_layers(layers, state, tick);
+    }
+    let ticking = start.elapsed();
+
+    // Keep the matrix moving between frames, but only time the drawing
+    let mut drawing = Duration::ZERO;
+    for tick in frames..frames * 2 {
+        update_layers(layers, state, tick);
+        let start = Instant::now();
+        terminal.draw(|frame| draw_layers(frame, area, layers, state))?;
+        drawing += start.elapsed();
+    }
+
+    println!("Size: {width}x{height}, frames: {frames}");
+    println!(
+        "Ticks: {:.3}s ({:.1} ticks/sec)",
+        ticking.as_secs_f64(),
+        frames as f64 / ticking.as_secs_f64()
+    );
+    println!(
+        "Frames: {:.3}s ({:.1} frames/sec)",
+        drawing.as_secs_f64(),
+        frames as f64 / drawing.as_secs_f64()
+    );
+    Ok(())
+}
@@ -0,0 +1,437 @@
+use crate::matrix::to_rgb;
+use crossterm::{cursor::MoveTo, queue};
+use ratatui::buffer::Cell;
+use ratatui::style::{Color, Modifier};
+use std::env;
+use std::fs;
+use std::io::{Error, ErrorKind, Result, Write};
+use std::path::PathBuf;
+
+// The 16 named colors in the order of their indexes
+const NAMED: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+// The levels of each channel in the 6x6x6 cube of the 256 colors
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+// Where `max_colors` is in the numbers of a compiled terminfo entry
+const MAX_COLORS: usize = 13;
+
+// How many colors the terminal can show
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColorDepth {
+    #[default]
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    // Only the first 8 colors, like the Linux console
+    Ansi8,
+    // No colors at all, like with `NO_COLOR`
+    Mono,
+}
+
+impl ColorDepth {
+    pub fn parse(depth: &str) -> Result<Self> {
+        match depth.to_lowercase().as_str() {
+            "truecolor" | "24bit" => Ok(Self::TrueColor),
+            "256" => Ok(Self::Ansi256),
+            "16" => Ok(Self::Ansi16),
+            "8" => Ok(Self::Ansi8),
+            "none" | "mono" => Ok(Self::Mono),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("color depth `{depth}` should be truecolor, 256, 16, 8 or none"),
+            )),
+        }
+    }
+
+    // Work out what the terminal supports from the environment and terminfo,
+    // going with 16 colors when there's nothing to go on
+    pub fn detect() -> Self {
+        if let Ok(colorterm) = env::var("COLORTERM") {
+            if colorterm == "truecolor" || colorterm == "24bit" {
+                return Self::TrueColor;
+            }
+        }
+        let term = env::var("TERM").unwrap_or_default();
+        match term.as_str() {
+            "" => return Self::Ansi16,
+            "dumb" => return Self::Mono,
+            // The Linux virtual console
+            "linux" => return Self::Ansi8,
+            _ => {}
+        }
+        if term.ends_with("-direct") {
+            return Self::TrueColor;
+        }
+        match terminfo_colors(&term) {
+            Some(colors) if colors >= 1 << 24 => Self::TrueColor,
+            Some(colors) if colors >= 256 => Self::Ansi256,
+            // Terminals with 8 colors nearly always take the bright ones too
+            Some(colors) if colors >= 8 => Self::Ansi16,
+            Some(_) => Self::Mono,
+            None if term.contains("256color") => Self::Ansi256,
+            None => Self::Ansi16,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::TrueColor => "truecolor",
+            Self::Ansi256 => "256",
+            Self::Ansi16 => "16",
+            Self::Ansi8 => "8",
+            Self::Mono => "none",
+        }
+    }
+
+    // Whether colors have to be written with the plain codes for the 16
+    // named colors
+    pub fn is_named(self) -> bool {
+        matches!(self, Self::Ansi16 | Self::Ansi8 | Self::Mono)
+    }
+
+    // The closest color the terminal can show
+    pub fn reduce(self, color: Color) -> Color {
+        match self {
+            Self::TrueColor | Self::Mono => color,
+            Self::Ansi256 => match color {
+                Color::Rgb(r, g, b) => Color::Indexed(nearest_indexed(r, g, b)),
+                color => color,
+            },
+            Self::Ansi16 => nearest(color, &NAMED),
+            Self::Ansi8 => nearest(color, &NAMED[..8]),
+        }
+    }
+}
+
+// Draw cells using the plain codes for the named colors, which older
+// terminals and serial consoles understand. Crossterm writes even the named
+// colors as one of the 256 colors.
+pub fn draw_named<'a, W: Write>(
+    out: &mut W,
+    content: impl Iterator<Item = (u16, u16, &'a Cell)>,
+) -> Result<()> {
+    let mut last: Option<(u16, u16)> = None;
+    let mut style = None;
+    for (x, y, cell) in content {
+        if last != Some((x.wrapping_sub(1), y)) {
+            queue!(out, MoveTo(x, y))?;
+        }
+        let current = (cell.fg, cell.bg, cell.modifier);
+        if style != Some(current) {
+            out.write_all(sgr(cell.fg, cell.bg, cell.modifier).as_bytes())?;
+            style = Some(current);
+        }
+        out.write_all(cell.symbol().as_bytes())?;
+        last = Some((x, y));
+    }
+    out.write_all(b"\x1b[0m")
+}
+
+// Reset and then set the style in one go
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec![0];
+    for (flag, code) in [
+        (Modifier::BOLD, 1),
+        (Modifier::DIM, 2),
+        (Modifier::ITALIC, 3),
+        (Modifier::UNDERLINED, 4),
+        (Modifier::SLOW_BLINK, 5),
+        (Modifier::REVERSED, 7),
+        (Modifier::HIDDEN, 8),
+        (Modifier::CROSSED_OUT, 9),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code);
+        }
+    }
+    codes.extend(named_code(fg));
+    codes.extend(named_code(bg).map(|code| code + 10));
+    let codes = codes
+        .iter()
+        .map(|code| code.to_string())
+        .collect::<Vec<String>>();
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+// The foreground code for a color, or none for the terminal's own
+fn named_code(color: Color) -> Option<u8> {
+    if color == Color::Reset {
+        return None;
+    }
+    let color = nearest(color, &NAMED);
+    let index = NAMED.iter().position(|named| *named == color)? as u8;
+    match index {
+        0..=7 => Some(30 + index),
+        _ => Some(90 + index - 8),
+    }
+}
+
+// The named color closest to any color, where the named colors are left as
+// they are if they're in the list
+fn nearest(color: Color, palette: &[Color]) -> Color {
+    if palette.contains(&color) || color == Color::Reset {
+        return color;
+    }
+    let (r, g, b) = to_rgb(color);
+    palette
+        .iter()
+        .copied()
+        .min_by_key(|named| distance((r, g, b), to_rgb(*named)))
+        .unwrap_or(color)
+}
+
+// The closest of the 256 colors past the named ones, either in the cube or
+// on the gray ramp
+fn nearest_indexed(r: u8, g: u8, b: u8) -> u8 {
+    let level = |c: u8| {
+        CUBE.iter()
+            .enumerate()
+            .min_by_key(|(_, level)| (i32::from(**level) - i32::from(c)).abs())
+            .map_or(0, |(i, _)| i as u8)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = 16 + 36 * ri + 6 * gi + bi;
+    let cube_rgb = (CUBE[ri as usize], CUBE[gi as usize], CUBE[bi as usize]);
+
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + 10 * gray;
+    match distance((r, g, b), (gray_level, gray_level, gray_level)) < distance((r, g, b), cube_rgb)
+    {
+        true => 232 + gray,
+        false => cube,
+    }
+}
+
+// The RGB value of one of the 256 colors
+pub fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => to_rgb(NAMED[index as usize]),
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE[(i / 36) as usize],
+                CUBE[(i / 6 % 6) as usize],
+                CUBE[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+// How far apart two colors look, weighted towards green like the eye is
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).unsigned_abs();
+    let (dr, dg, db) = (d(r1, r2), d(g1, g2), d(b1, b2));
+    2 * dr * dr + 4 * dg * dg + 3 * db * db
+}
+
+// How many colors terminfo says the terminal has
+fn terminfo_colors(term: &str) -> Option<u32> {
+    let first = term.chars().next()?;
+    let dirs = env::var_os("TERMINFO")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(env::var_os("HOME").map(|home| PathBuf::from(home).join(".terminfo")))
+        .chain(
+            env::var("TERMINFO_DIRS")
+                .unwrap_or_default()
+                .split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+                .collect::<Vec<PathBuf>>(),
+        )
+        .chain(["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo"].map(PathBuf::from));
+    dirs.flat_map(|dir| {
+        // Entries are under their first letter, or its hex code on macOS
+        [
+            dir.join(first.to_string()).join(term),
+            dir.join(format!("{:x}", first as u32)).join(term),
+        ]
+    })
+    .find_map(|path| fs::read(path).ok())
+    .and_then(|entry| parse_colors(&entry))
+}
+
+// Read `max_colors` out of a compiled terminfo entry, in either the legacy
+// format with 16 bit numbers or the newer one with 32 bit numbers
+fn parse_colors(entry: &[u8]) -> Option<u32> {
+    let short = |i: usize| Some(u16::from_le_bytes([*entry.get(i)?, *entry.get(i + 1)?]));
+    let number_size = match short(0)? {
+        0o432 => 2,
+        0o1036 => 4,
+        _ => return None,
+    };
+    let names = usize::from(short(2)?);
+    let bools = usize::from(short(4)?);
+    let numbers = usize::from(short(6)?);
+    if numbers <= MAX_COLORS {
+        return None;
+    }
+    // Numbers start on an even byte
+    let start = (12 + names + bools).next_multiple_of(2) + MAX_COLORS * number_size;
+    let bytes = entry.get(start..start + number_size)?;
+    let colors = match number_size {
+        2 => i32::from(i16::from_le_bytes([bytes[0], bytes[1]])),
+        _ => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+    };
+    u32::try_from(colors).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A compiled terminfo entry with no strings, and the numbers written with
+    // the given size in bytes
+    fn entry(
+        magic: u16,
+        number_size: usize,
+        names: &str,
+        bools: usize,
+        numbers: &[i32],
+    ) -> Vec<u8> {
+        let names = format!("{names}\0");
+        let mut entry = Vec::new();
+        for short in [
+            magic,
+            names.len() as u16,
+            bools as u16,
+            numbers.len() as u16,
+            0,
+            0,
+        ] {
+            entry.extend(short.to_le_bytes());
+        }
+        entry.extend(names.as_bytes());
+        entry.extend(vec![1; bools]);
+        if entry.len() % 2 == 1 {
+            entry.push(0);
+        }
+        for number in numbers {
+            entry.extend(&number.to_le_bytes()[..number_size]);
+        }
+        entry
+    }
+
+    // The numbers of an entry with the columns and `max_colors` set, and the
+    // rest missing
+    fn numbers(max_colors: i32) -> Vec<i32> {
+        let mut numbers = vec![-1; MAX_COLORS + 2];
+        numbers[0] = 80;
+        numbers[MAX_COLORS] = max_colors;
+        numbers
+    }
+
+    #[test]
+    fn legacy_terminfo() {
+        // The numbers start on an even byte, so this one has a byte of padding
+        // before them and the next one doesn't
+        let padded = entry(0o432, 2, "xterm-256color|xterm 256", 4, &numbers(256));
+        assert_eq!(parse_colors(&padded), Some(256));
+        let unpadded = entry(0o432, 2, "xterm|xterm", 4, &numbers(8));
+        assert_eq!(parse_colors(&unpadded), Some(8));
+    }
+
+    #[test]
+    fn extended_terminfo() {
+        let entry = entry(
+            0o1036,
+            4,
+            "xterm-direct|xterm with direct color",
+            3,
+            &numbers(1 << 24),
+        );
+        assert_eq!(parse_colors(&entry), Some(1 << 24));
+    }
+
+    #[test]
+    fn terminfo_without_colors() {
+        // A missing number is -1
+        assert_eq!(
+            parse_colors(&entry(0o432, 2, "dumb|80-column dumb tty", 2, &numbers(-1))),
+            None
+        );
+        assert_eq!(
+            parse_colors(&entry(0o432, 2, "vt52|dec vt52", 2, &[80, -1, 24])),
+            None
+        );
+        assert_eq!(
+            parse_colors(&entry(0o542, 2, "bad|bad magic", 2, &numbers(8))),
+            None
+        );
+        let truncated = entry(0o1036, 4, "xterm|xterm", 2, &numbers(256));
+        assert_eq!(parse_colors(&truncated[..truncated.len() - 9]), None);
+        assert_eq!(parse_colors(&[]), None);
+    }
+
+    #[test]
+    fn nearest_in_cube() {
+        assert_eq!(nearest_indexed(255, 0, 0), 196);
+        assert_eq!(nearest_indexed(0, 255, 0), 46);
+        assert_eq!(nearest_indexed(0, 0, 0), 16);
+        assert_eq!(nearest_indexed(255, 255, 255), 231);
+        // Each channel goes to its closest level: 100 to 95, 140 to 135 and
+        // 200 to 215
+        assert_eq!(nearest_indexed(100, 140, 200), 16 + 36 + 6 * 2 + 4);
+    }
+
+    #[test]
+    fn nearest_on_gray_ramp() {
+        assert_eq!(nearest_indexed(128, 128, 128), 244);
+        assert_eq!(nearest_indexed(8, 8, 8), 232);
+        assert_eq!(nearest_indexed(238, 238, 238), 255);
+        // Close to gray but not quite
+        assert_eq!(nearest_indexed(100, 102, 98), 241);
+    }
+
+    #[test]
+    fn indexed_colors_map_back_to_themselves() {
+        for index in 16..=255 {
+            let (r, g, b) = indexed_rgb(index);
+            assert_eq!(
+                nearest_indexed(r, g, b),
+                index,
+                "{index} is {:?}",
+                (r, g, b)
+            );
+        }
+    }
+
+    #[test]
+    fn reduce_to_depth() {
+        assert_eq!(
+            ColorDepth::Ansi256.reduce(Color::Rgb(0, 255, 0)),
+            Color::Indexed(46)
+        );
+        assert_eq!(ColorDepth::Ansi256.reduce(Color::Green), Color::Green);
+        assert_eq!(
+            ColorDepth::Ansi16.reduce(Color::Rgb(250, 250, 250)),
+            Color::White
+        );
+        assert_eq!(ColorDepth::Ansi8.reduce(Color::LightGreen), Color::Green);
+        assert_eq!(
+            ColorDepth::TrueColor.reduce(Color::Rgb(1, 2, 3)),
+            Color::Rgb(1, 2, 3)
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use crate::feed::FeedConfig;
+use crate::intro::IntroConfig;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::PathBuf;
+
+// Settings read from `config.yaml` in the config directory, or the file in
+// `RJMATRIX_CONFIG`. Every section is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    // Keys bound to actions on top of the defaults, e.g. `x: quit`
+    pub keys: BTreeMap<String, String>,
+    // Which values from `--feed` steer the rain
+    pub feed: FeedConfig,
+    // What `--intro` types out before the rain
+    pub intro: IntroConfig,
+}
+
+impl Config {
+    // A missing file is fine, but one that can't be read or parsed is not
+    pub fn load() -> Result<Self> {
+        let Some(path) = path() else {
+            return Ok(Self::default());
+        };
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+        serde_yaml::from_str(&text).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid config `{}`: {e}", path.display()),
+            )
+        })
+    }
+}
+
+fn path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("RJMATRIX_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(dir.join("rjmatrix").join("config.yaml"))
+}
@@ -0,0 +1,289 @@
+use crate::glyphs::launch_glyphs;
+use crate::matrix::stream_color;
+use crate::pane::Pane;
+use crate::react::{set_density, speed};
+use log::{debug, warn};
+use rand::{thread_rng, Rng};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{stdin, BufRead, BufReader, Error, Read, Result};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How often the smoothed values move towards the latest ones
+const UPDATE: Duration = Duration::from_millis(100);
+// How long to wait for more to be written after reaching the end
+const FOLLOW: Duration = Duration::from_millis(250);
+// The key a line with just a number is for
+const BARE_KEY: &str = "value";
+
+// Which values in the feed steer what, from the `feed` section of the config
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeedConfig {
+    // How many streams there are
+    pub density: Option<Mapping>,
+    // How fast the rain falls
+    pub speed: Option<Mapping>,
+    // The color of the rain, from green at the bottom of the range to red at
+    // the top
+    pub hue: Option<Mapping>,
+    // The key whose values are dropped into the rain as text
+    pub message: Option<String>,
+    // Seconds for the rain to get most of the way to a new value
+    pub smoothing: f32,
+}
+
+impl Default for FeedConfig {
+    fn default() -> Self {
+        Self {
+            density: Some(Mapping::default()),
+            speed: Some(Mapping::default()),
+            hue: None,
+            message: None,
+            smoothing: 1.0,
+        }
+    }
+}
+
+// A key in the feed and the range its values go over
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Mapping {
+    pub key: String,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Default for Mapping {
+    fn default() -> Self {
+        Self {
+            key: BARE_KEY.to_string(),
+            min: 0.0,
+            max: 100.0,
+        }
+    }
+}
+
+impl Mapping {
+    // Where a value is in the range, from 0.0 to 1.0
+    fn level(&self, value: f32) -> f32 {
+        let range = self.max - self.min;
+        match range == 0.0 {
+            true => 0.0,
+            false => ((value - self.min) / range).clamp(0.0, 1.0),
+        }
+    }
+}
+
+// A value from the feed, eased towards the latest one so the rain doesn't
+// jump on every sample
+#[derive(Clone, Copy)]
+struct Smoothed {
+    target: f32,
+    current: f32,
+}
+
+// Reads values written to a file, FIFO or stdin and steers the rain with them
+pub struct Feed {
+    config: FeedConfig,
+    lines: Receiver<String>,
+    values: HashMap<String, Smoothed>,
+    // The latest message that hasn't found a line to fall in yet
+    message: Option<String>,
+    updated: Instant,
+    next: Instant,
+}
+
+impl Feed {
+    // A path of `-` reads from stdin
+    pub fn new(path: &str, config: FeedConfig) -> Result<Self> {
+        let reader: Box<dyn Read + Send> = match path {
+            "-" => Box::new(stdin()),
+            path => {
+                // Opening a FIFO waits for a writer, so do that on the
+                // reading thread and only check it's there now
+                fs::metadata(path)
+                    .map_err(|e| Error::new(e.kind(), format!("can't read feed `{path}`: {e}")))?;
+                Box::new(Opening(path.to_string(), None))
+            }
+        };
+        Ok(Self {
+            config,
+            lines: read_lines(reader, path != "-"),
+            values: HashMap::new(),
+            message: None,
+            updated: Instant::now(),
+            next: Instant::now(),
+        })
+    }
+
+    pub fn next(&self) -> Instant {
+        self.next
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        now >= self.next
+    }
+
+    // Take in everything written since the last update and ease every value
+    // towards its latest
+    pub fn update(&mut self, now: Instant) {
+        while let Ok(line) = self.lines.try_recv() {
+            self.parse(&line);
+        }
+        let seconds = now.saturating_duration_since(self.updated).as_secs_f32();
+        let ease = match self.config.smoothing > 0.0 {
+            true => 1.0 - (-seconds / self.config.smoothing).exp(),
+            false => 1.0,
+        };
+        for value in self.values.values_mut() {
+            value.current += (value.target - value.current) * ease;
+        }
+        self.updated = now;
+        self.next = now + UPDATE;
+    }
+
+    // Either a number, or `key=value` where the value is a number or text
+    // for the message
+    fn parse(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return;
+        }
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => (BARE_KEY, line),
+        };
+        if self.config.message.as_deref() == Some(key) {
+            self.message = Some(value.to_string());
+        }
+        match value.parse::<f32>() {
+            Ok(value) if value.is_finite() => {
+                // The first value is taken as it is, with nothing to ease from
+                self.values
+                    .entry(key.to_string())
+                    .and_modify(|smoothed| smoothed.target = value)
+                    .or_insert(Smoothed {
+                        target: value,
+                        current: value,
+                    });
+            }
+            _ if self.config.message.as_deref() == Some(key) => {}
+            _ => debug!("Ignoring `{line}` from the feed"),
+        }
+    }
+
+    // The smoothed level of a mapping, once its key has had a value
+    fn level(&self, mapping: Option<&Mapping>) -> Option<f32> {
+        let mapping = mapping?;
+        let value = self.values.get(&mapping.key)?;
+        Some(mapping.level(value.current))
+    }
+
+    pub fn apply(&mut self, panes: &mut [Pane]) {
+        let density = self.level(self.config.density.as_ref());
+        let speed_level = self.level(self.config.speed.as_ref());
+        let hue = self.level(self.config.hue.as_ref());
+        for pane in panes.iter_mut() {
+            if let Some(level) = speed_level {
+                pane.state.speed = speed(level);
+            }
+            if let Some(level) = hue {
+                pane.state.hue = Some(120.0 * (1.0 - level));
+            }
+            if let Some(level) = density {
+                set_density(pane, |_, _| level);
+            }
+        }
+        if let Some(message) = self.message.take() {
+            if !launch(&message, panes) {
+                self.message = Some(message);
+            }
+        }
+    }
+}
+
+// Drop the message into an empty line of the nearest layer, in a random
+// pane. A line with a stream still on it would share the message with that
+// stream's head. Returns whether there was a line to drop it into.
+fn launch(message: &str, panes: &mut [Pane]) -> bool {
+    let mut rng = thread_rng();
+    let mut free: Vec<(usize, usize)> = Vec::new();
+    for (i, pane) in panes.iter().enumerate() {
+        let Some(layer) = pane.layers.iter().rfind(|layer| !layer.draining) else {
+            continue;
+        };
+        free.extend(
+            layer
+                .matrix
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| line.is_idle())
+                .map(|(j, _)| (i, j)),
+        );
+    }
+    if free.is_empty() {
+        return false;
+    }
+    let (i, j) = free[rng.gen_range(0..free.len())];
+    let pane = &mut panes[i];
+    let glyphs = launch_glyphs(message, pane.state.smooth);
+    if glyphs.is_empty() {
+        return true;
+    }
+    debug!("Dropping `{message}` into the rain");
+    let color = stream_color(&pane.state.color);
+    if let Some(layer) = pane.layers.iter_mut().rfind(|layer| !layer.draining) {
+        layer.matrix[j].launch(glyphs, color);
+    }
+    true
+}
+
+// A file that is only opened when it's first read
+struct Opening(String, Option<File>);
+
+impl Read for Opening {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let file = match &mut self.1 {
+            Some(file) => file,
+            None => self.1.insert(File::open(&self.0)?),
+        };
+        file.read(buf)
+    }
+}
+
+// Read lines on their own thread. A file or FIFO is followed for more after
+// reaching the end, and stdin stops there.
+fn read_lines(reader: Box<dyn Read + Send>, follow: bool) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut line = String::new();
+        loop {
+            match reader.read_line(&mut line) {
+                Ok(0) if follow => thread::sleep(FOLLOW),
+                Ok(0) => {
+                    if !line.is_empty() {
+                        let _ = sender.send(line);
+                    }
+                    return;
+                }
+                // Hold on to part of a line until the rest is written
+                Ok(_) if !line.ends_with('\n') => {}
+                Ok(_) => {
+                    if sender.send(std::mem::take(&mut line)).is_err() {
+                        return;
+                    }
+                }
+                Err(e) => {
+                    warn!("Failed to read the feed: {e}");
+                    return;
+                }
+            }
+        }
+    });
+    receiver
+}
@@ -0,0 +1,164 @@
+use rand::{thread_rng, Rng};
+use std::fs;
+use std::io::{stdin, Error, ErrorKind, Read, Result};
+use std::sync::Mutex;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+                        abcdefghijklmnopqrstuvwxyz\
+                        0123456789)(}{][*&^%$#@!~";
+
+// Graphemes made of more than one char, shared by every line. They are never
+// freed, so only so many are kept and the rest are shown as `?`.
+static INTERNED: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+const MAX_INTERNED: usize = 4096;
+
+// A single cell's worth of text that is cheap to copy around. Most graphemes
+// are one char, and the rest are interned and stored by their index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symbol {
+    Char(char),
+    Interned(u32),
+}
+
+impl Symbol {
+    pub fn new(grapheme: &str) -> Self {
+        let mut chars = grapheme.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Symbol::Char(c),
+            (None, _) => Symbol::Char(' '),
+            _ => intern(grapheme),
+        }
+    }
+
+    // Get the text of the symbol, using the buffer for a single char
+    pub fn as_str(self, buf: &mut [u8; 4]) -> &str {
+        match self {
+            Symbol::Char(c) => c.encode_utf8(buf),
+            Symbol::Interned(i) => INTERNED.lock().unwrap()[i as usize],
+        }
+    }
+}
+
+fn intern(grapheme: &str) -> Symbol {
+    let mut interned = INTERNED.lock().unwrap();
+    if let Some(i) = interned.iter().position(|known| *known == grapheme) {
+        return Symbol::Interned(i as u32);
+    }
+    if interned.len() >= MAX_INTERNED {
+        return Symbol::Char('?');
+    }
+    interned.push(Box::leak(grapheme.into()));
+    Symbol::Interned(interned.len() as u32 - 1)
+}
+
+// Where the glyphs written into each stream come from
+#[derive(Clone, Debug)]
+pub enum Glyphs {
+    // A random character from the built in charset
+    Random,
+    // The graphemes of some piped input or files, read in order
+    Source(Vec<Symbol>),
+    // Nothing but the text launched into each line, like in `tail`
+    Launched,
+}
+
+impl Glyphs {
+    // Read every source in order, where `-` is stdin
+    pub fn from_sources(paths: &[String], smooth: bool) -> Result<Self> {
+        let mut bytes = Vec::new();
+        for path in paths {
+            let read = match path.as_str() {
+                "-" => stdin().read_to_end(&mut bytes).map(|_| ()),
+                path => fs::read(path).map(|read| bytes.extend(read)),
+            };
+            read.map_err(|e| Error::new(e.kind(), format!("can't read source `{path}`: {e}")))?;
+            // Keep the end of one file from running into the start of the next
+            bytes.push(b'\n');
+        }
+
+        Self::from_text(&String::from_utf8_lossy(&bytes), smooth)
+    }
+
+    // Write the text into the streams in order. Smooth motion takes two
+    // glyphs to move a cell, so each one is doubled to show them all.
+    pub fn from_text(text: &str, smooth: bool) -> Result<Self> {
+        let glyphs = launch_glyphs(text, smooth);
+        if glyphs.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "source contains no printable characters",
+            ));
+        }
+        Ok(Glyphs::Source(glyphs))
+    }
+
+    // Get the next glyph for a line, advancing its position in the source
+    pub fn next_glyph(&self, cursor: &mut usize) -> Option<Symbol> {
+        match self {
+            Glyphs::Random => {
+                let idx = thread_rng().gen_range(0..CHARSET.len());
+                Some(Symbol::Char(CHARSET[idx] as char))
+            }
+            Glyphs::Source(glyphs) => {
+                // Loop back to the start once the input is exhausted
+                let glyph = glyphs[*cursor % glyphs.len()];
+                *cursor = (*cursor + 1) % glyphs.len();
+                Some(glyph)
+            }
+            Glyphs::Launched => None,
+        }
+    }
+
+    // Whether streams start on their own or only when text is launched
+    pub fn spawns(&self) -> bool {
+        !matches!(self, Glyphs::Launched)
+    }
+
+    // Where a new line should start reading from so columns don't all match
+    pub fn start(&self) -> usize {
+        match self {
+            Glyphs::Random | Glyphs::Launched => 0,
+            Glyphs::Source(glyphs) => thread_rng().gen_range(0..glyphs.len()),
+        }
+    }
+}
+
+// Split text into single cell graphemes. Control characters and whitespace
+// are collapsed into a single space, and graphemes that don't take up exactly
+// one cell are dropped so they can't break the grid.
+pub fn printable_graphemes(text: &str) -> Vec<Symbol> {
+    let space = Symbol::Char(' ');
+    let mut glyphs: Vec<Symbol> = Vec::new();
+    for grapheme in text.graphemes(true) {
+        if grapheme
+            .chars()
+            .any(|c| c.is_control() || c.is_whitespace())
+        {
+            if glyphs.last().is_some_and(|last| *last != space) {
+                glyphs.push(space);
+            }
+        } else if grapheme.width() == 1 {
+            glyphs.push(Symbol::new(grapheme));
+        }
+    }
+    // A source of only whitespace has nothing to show
+    if glyphs.iter().all(|glyph| *glyph == space) {
+        glyphs.clear();
+    }
+    glyphs
+}
+
+// The glyphs to write into lines for some text. Each glyph takes up two
+// cells when moving smoothly.
+pub fn launch_glyphs(text: &str, smooth: bool) -> Vec<Symbol> {
+    let glyphs = printable_graphemes(text);
+    match smooth {
+        true => glyphs
+            .into_iter()
+            .flat_map(|glyph| [glyph, glyph])
+            .collect(),
+        false => glyphs,
+    }
+}
@@ -0,0 +1,633 @@
+use crate::colors::{draw_named, ColorDepth};
+use crate::font;
+use crate::matrix::to_rgb;
+use crossterm::terminal;
+use log::info;
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use ratatui::{
+    backend::{Backend, CrosstermBackend, WindowSize},
+    buffer::{Buffer, Cell},
+    layout::{Position, Rect, Size},
+    style::{Color, Modifier},
+};
+use std::env;
+use std::io::{stdout, Error, ErrorKind, Result, Stdout, Write};
+
+// Pixels drawn for each cell before the image is scaled to fit the screen
+const CELL_WIDTH: usize = 6;
+const CELL_HEIGHT: usize = 9;
+// Cell size in screen pixels to use for Sixel when the terminal won't say
+const DEFAULT_CELL_PIXELS: (usize, usize) = (8, 16);
+// How bright a pixel has to be before it starts to glow, and how much
+const GLOW_THRESHOLD: f32 = 0.35;
+const GLOW: f32 = 1.4;
+const GLOW_RADIUS: usize = 2;
+// Fast zlib level for kitty frames, which are sent many times a second
+const KITTY_COMPRESSION: u8 = 1;
+
+// Ways of sending images to the terminal
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Protocol {
+    Kitty,
+    Sixel,
+}
+
+impl Protocol {
+    // `auto` picks whatever the terminal supports, which may be nothing
+    pub fn choose(mode: &str) -> Result<Option<Self>> {
+        match mode.to_lowercase().as_str() {
+            "auto" => Ok(detect()),
+            "kitty" => Ok(Some(Protocol::Kitty)),
+            "sixel" => Ok(Some(Protocol::Sixel)),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("graphics `{mode}` should be auto, kitty or sixel"),
+            )),
+        }
+    }
+}
+
+// Guess from the environment which protocol the terminal understands
+pub fn detect() -> Option<Protocol> {
+    let var = |name| env::var(name).unwrap_or_default();
+    let term = var("TERM");
+    let program = var("TERM_PROGRAM");
+    // Images don't make it through tmux unless it is set up to pass them on
+    if env::var_os("TMUX").is_some() {
+        info!("Not using graphics inside tmux");
+        return None;
+    }
+    if env::var_os("KITTY_WINDOW_ID").is_some()
+        || term.contains("kitty")
+        || term.contains("ghostty")
+        || matches!(program.as_str(), "WezTerm" | "ghostty")
+    {
+        Some(Protocol::Kitty)
+    } else if term.contains("sixel")
+        || term.starts_with("foot")
+        || term.starts_with("mlterm")
+        || term.starts_with("contour")
+        || program == "mlterm"
+    {
+        Some(Protocol::Sixel)
+    } else {
+        None
+    }
+}
+
+// An RGB image with each channel from 0.0 to 1.0
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[f32; 3]>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0.0; 3]; width * height],
+        }
+    }
+
+    fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: [f32; 3]) {
+        for row in y..(y + height).min(self.height) {
+            for col in x..(x + width).min(self.width) {
+                self.pixels[row * self.width + col] = color;
+            }
+        }
+    }
+
+    // Every pixel as bytes in RGB order
+    pub fn rgb(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|pixel| pixel.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8))
+            .collect()
+    }
+
+    // Resize with bilinear filtering, so scaled up glyphs stay soft
+    pub fn scale(&self, width: usize, height: usize) -> Self {
+        let mut scaled = Self::new(width, height);
+        if self.width == 0 || self.height == 0 {
+            return scaled;
+        }
+        let sx = self.width as f32 / width as f32;
+        let sy = self.height as f32 / height as f32;
+        for y in 0..height {
+            let fy = ((y as f32 + 0.5) * sy - 0.5).max(0.0);
+            let y0 = (fy as usize).min(self.height - 1);
+            let y1 = (y0 + 1).min(self.height - 1);
+            let ty = fy - y0 as f32;
+            for x in 0..width {
+                let fx = ((x as f32 + 0.5) * sx - 0.5).max(0.0);
+                let x0 = (fx as usize).min(self.width - 1);
+                let x1 = (x0 + 1).min(self.width - 1);
+                let tx = fx - x0 as f32;
+                let at = |x: usize, y: usize| self.pixels[y * self.width + x];
+                let (a, b, c, d) = (at(x0, y0), at(x1, y0), at(x0, y1), at(x1, y1));
+                scaled.pixels[y * width + x] = [0, 1, 2].map(|i| {
+                    let top = a[i] + (b[i] - a[i]) * tx;
+                    let bottom = c[i] + (d[i] - c[i]) * tx;
+                    top + (bottom - top) * ty
+                });
+            }
+        }
+        scaled
+    }
+}
+
+// Draw every cell of the buffer with the built in font
+pub fn rasterize(buffer: &Buffer) -> Image {
+    let area = buffer.area;
+    let mut image = Image::new(
+        usize::from(area.width) * CELL_WIDTH,
+        usize::from(area.height) * CELL_HEIGHT,
+    );
+    for y in 0..area.height {
+        for x in 0..area.width {
+            let cell = &buffer[(area.x + x, area.y + y)];
+            draw_cell(
+                &mut image,
+                usize::from(x) * CELL_WIDTH,
+                usize::from(y) * CELL_HEIGHT,
+                cell,
+            );
+        }
+    }
+    image
+}
+
+fn draw_cell(image: &mut Image, x: usize, y: usize, cell: &Cell) {
+    let background = match cell.bg {
+        Color::Reset => [0.0; 3],
+        color => scale_color(to_rgb(color), 1.0),
+    };
+    image.fill(x, y, CELL_WIDTH, CELL_HEIGHT, background);
+
+    let brightness = if cell.modifier.contains(Modifier::DIM) {
+        0.45
+    } else if cell.modifier.contains(Modifier::BOLD) {
+        1.0
+    } else {
+        0.85
+    };
+    let color = match cell.fg {
+        Color::Reset => scale_color((200, 200, 200), brightness),
+        color => scale_color(to_rgb(color), brightness),
+    };
+    let (w, h) = (CELL_WIDTH, CELL_HEIGHT);
+    let c = cell.symbol().chars().next().unwrap_or(' ');
+    match c {
+        // Shapes used by the smooth motion and the help box
+        '█' => image.fill(x, y, w, h, color),
+        '▀' => image.fill(x, y, w, h / 2, color),
+        '▄' => image.fill(x, y + h / 2, w, h - h / 2, color),
+        '▌' => image.fill(x, y, w / 2, h, color),
+        '▐' => image.fill(x + w / 2, y, w - w / 2, h, color),
+        '─' | '│' | '┌' | '┐' | '└' | '┘' => {
+            let (left, right) = (matches!(c, '─' | '┐' | '┘'), matches!(c, '─' | '┌' | '└'));
+            let (up, down) = (matches!(c, '│' | '└' | '┘'), matches!(c, '│' | '┌' | '┐'));
+            let (mx, my) = (x + w / 2, y + h / 2);
+            if left {
+                image.fill(x, my, w / 2 + 1, 1, color);
+            }
+            if right {
+                image.fill(mx, my, w - w / 2, 1, color);
+            }
+            if up {
+                image.fill(mx, y, 1, h / 2 + 1, color);
+            }
+            if down {
+                image.fill(mx, my, 1, h - h / 2, color);
+            }
+        }
+        c => {
+            // Anything the font doesn't have is shown as a small block
+            let Some(rows) = font::glyph(c) else {
+                image.fill(x + 1, y + 2, font::WIDTH - 2, font::HEIGHT - 2, color);
+                return;
+            };
+            for row in 0..font::HEIGHT {
+                for col in 0..font::WIDTH {
+                    if font::lit(rows, col, row) {
+                        image.fill(x + col, y + row + 1, 1, 1, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn scale_color((r, g, b): (u8, u8, u8), brightness: f32) -> [f32; 3] {
+    [r, g, b].map(|c| c as f32 / 255.0 * brightness)
+}
+
+// Blur the brightest pixels and add them back on top, so the heads glow
+pub fn bloom(image: &mut Image) {
+    let mut glow: Vec<[f32; 3]> = image
+        .pixels
+        .iter()
+        .map(|pixel| {
+            let luminance = 0.2126 * pixel[0] + 0.7152 * pixel[1] + 0.0722 * pixel[2];
+            let amount = ((luminance - GLOW_THRESHOLD) / (1.0 - GLOW_THRESHOLD)).max(0.0);
+            pixel.map(|c| c * amount)
+        })
+        .collect();
+    // Two box blurs each way are close enough to a gaussian
+    for _ in 0..2 {
+        blur(&mut glow, image.width, image.height, 1, image.width);
+        blur(&mut glow, image.height, image.width, image.width, 1);
+    }
+    for (pixel, glow) in image.pixels.iter_mut().zip(glow.iter()) {
+        for i in 0..3 {
+            pixel[i] += glow[i] * GLOW;
+        }
+    }
+}
+
+// Box blur each line of pixels, where `step` moves along a line and `stride`
+// moves to the next line
+fn blur(pixels: &mut [[f32; 3]], len: usize, lines: usize, step: usize, stride: usize) {
+    let mut line = vec![[0.0; 3]; len];
+    let size = (GLOW_RADIUS * 2 + 1) as f32;
+    for l in 0..lines {
+        let at = |i: usize| l * stride + i * step;
+        let mut sum = [0.0; 3];
+        for i in 0..=GLOW_RADIUS.min(len.saturating_sub(1)) {
+            let pixel = pixels[at(i)];
+            (0..3).for_each(|c| sum[c] += pixel[c]);
+        }
+        for (i, out) in line.iter_mut().enumerate() {
+            *out = sum.map(|c| c / size);
+            if let Some(add) = (i + GLOW_RADIUS + 1 < len).then(|| pixels[at(i + GLOW_RADIUS + 1)])
+            {
+                (0..3).for_each(|c| sum[c] += add[c]);
+            }
+            if i >= GLOW_RADIUS {
+                let remove = pixels[at(i - GLOW_RADIUS)];
+                (0..3).for_each(|c| sum[c] -= remove[c]);
+            }
+        }
+        for (i, pixel) in line.iter().enumerate() {
+            pixels[at(i)] = *pixel;
+        }
+    }
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => out.push(BASE64[((n >> (18 - 6 * i)) & 63) as usize]),
+                false => out.push(b'='),
+            }
+        }
+    }
+    out
+}
+
+// Send an image with the kitty graphics protocol, stretched by the terminal
+// over the given number of cells. The image always has the same id, so each
+// frame replaces the last. The pixels are compressed, since most of the
+// screen is black and a whole frame would otherwise be megabytes.
+pub fn encode_kitty(image: &Image, columns: u16, rows: u16) -> Vec<u8> {
+    let data = base64(&compress_to_vec_zlib(&image.rgb(), KITTY_COMPRESSION));
+    let mut out = Vec::with_capacity(data.len() + data.len() / 4096 * 16 + 64);
+    let chunks = data.chunks(4096).collect::<Vec<&[u8]>>();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        match i {
+            0 => write!(
+                out,
+                "\x1b_Ga=T,f=24,o=z,s={},v={},c={columns},r={rows},i=1,q=2,C=1,m={more};",
+                image.width, image.height
+            ),
+            _ => write!(out, "\x1b_Gm={more};"),
+        }
+        .expect("writing to a Vec can't fail");
+        out.extend_from_slice(chunk);
+        out.extend_from_slice(b"\x1b\\");
+    }
+    out
+}
+
+// Send an image as Sixel, with the colors rounded to a 6x6x6 cube
+pub fn encode_sixel(image: &Image) -> Vec<u8> {
+    let levels = |pixel: [f32; 3]| pixel.map(|c| (c.clamp(0.0, 1.0) * 5.0).round() as usize);
+    let indexes: Vec<usize> = image
+        .pixels
+        .iter()
+        .map(|pixel| {
+            let [r, g, b] = levels(*pixel);
+            r * 36 + g * 6 + b
+        })
+        .collect();
+
+    let mut out = Vec::new();
+    let mut push = |text: String| out.extend_from_slice(text.as_bytes());
+    push(format!("\x1bP0;1;0q\"1;1;{};{}", image.width, image.height));
+    let mut used = [false; 216];
+    indexes.iter().for_each(|i| used[*i] = true);
+    for (i, _) in used.iter().enumerate().filter(|(_, used)| **used) {
+        let percent = |level: usize| level * 100 / 5;
+        push(format!(
+            "#{i};2;{};{};{}",
+            percent(i / 36),
+            percent(i / 6 % 6),
+            percent(i % 6)
+        ));
+    }
+
+    // Each band is six rows of pixels, drawn one color at a time
+    let mut sixels: Vec<Option<Vec<u8>>> = vec![None; 216];
+    for band in (0..image.height).step_by(6) {
+        let mut colors = Vec::new();
+        for row in band..(band + 6).min(image.height) {
+            let bit = 1 << (row - band);
+            for x in 0..image.width {
+                let color = indexes[row * image.width + x];
+                let line = sixels[color].get_or_insert_with(|| {
+                    colors.push(color);
+                    vec![0; image.width]
+                });
+                line[x] |= bit;
+            }
+        }
+        for (n, color) in colors.iter().enumerate() {
+            let line = sixels[*color].take().unwrap_or_default();
+            push(format!("#{color}"));
+            // Repeated sixels are run length encoded
+            let mut x = 0;
+            while x < line.len() {
+                let run = line[x..].iter().take_while(|s| **s == line[x]).count();
+                let sixel = (line[x] + 63) as char;
+                match run {
+                    1..=3 => push(sixel.to_string().repeat(run)),
+                    _ => push(format!("!{run}{sixel}")),
+                }
+                x += run;
+            }
+            // `$` goes back to the start of the band for the next color
+            if n + 1 < colors.len() {
+                push("$".to_string());
+            }
+        }
+        push("-".to_string());
+    }
+    push("\x1b\\".to_string());
+    out
+}
+
+// Draws through crossterm as usual, or collects the cells and draws the whole
+// screen as an image when there is a protocol to send it with
+pub struct GraphicsBackend {
+    text: CrosstermBackend<Stdout>,
+    protocol: Option<Protocol>,
+    buffer: Buffer,
+    // Write text with the plain codes for the named colors
+    named: bool,
+}
+
+impl GraphicsBackend {
+    pub fn new(protocol: Option<Protocol>, depth: ColorDepth) -> Self {
+        if let Some(protocol) = protocol {
+            info!("Drawing with {protocol:?} graphics");
+        }
+        Self {
+            text: CrosstermBackend::new(stdout()),
+            protocol,
+            buffer: Buffer::empty(Rect::ZERO),
+            named: depth.is_named(),
+        }
+    }
+
+    fn fit_buffer(&mut self) -> Result<()> {
+        let size = self.text.size()?;
+        let area = Rect::new(0, 0, size.width, size.height);
+        if self.buffer.area != area {
+            self.buffer.resize(area);
+        }
+        Ok(())
+    }
+
+    fn draw_image(&mut self, protocol: Protocol) -> Result<()> {
+        self.fit_buffer()?;
+        let mut image = rasterize(&self.buffer);
+        bloom(&mut image);
+        let area = self.buffer.area;
+        let frame = match protocol {
+            Protocol::Kitty => encode_kitty(&image, area.width, area.height),
+            Protocol::Sixel => {
+                // Sixel pixels are screen pixels, so scale to the real size
+                let (width, height) = match terminal::window_size() {
+                    Ok(size) if size.width > 0 && size.height > 0 => {
+                        (usize::from(size.width), usize::from(size.height))
+                    }
+                    _ => (
+                        usize::from(area.width) * DEFAULT_CELL_PIXELS.0,
+                        usize::from(area.height) * DEFAULT_CELL_PIXELS.1,
+                    ),
+                };
+                encode_sixel(&image.scale(width, height))
+            }
+        };
+        self.text.set_cursor_position(Position::ORIGIN)?;
+        self.text.write_all(&frame)
+    }
+}
+
+impl Drop for GraphicsBackend {
+    fn drop(&mut self) {
+        // Take the last frame off the screen
+        if self.protocol == Some(Protocol::Kitty) {
+            let _ = self.text.write_all(b"\x1b_Ga=d,d=A,q=2\x1b\\");
+            let _ = Write::flush(&mut self.text);
+        }
+    }
+}
+
+impl Backend for GraphicsBackend {
+    fn draw<'a, I>(&mut self, content: I) -> Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        match (self.protocol, self.named) {
+            (None, true) => return draw_named(&mut self.text, content),
+            (None, false) => return self.text.draw(content),
+            _ => {}
+        }
+        self.fit_buffer()?;
+        for (x, y, cell) in content {
+            if let Some(buffer_cell) = self.buffer.cell_mut((x, y)) {
+                *buffer_cell = cell.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> Result<()> {
+        self.text.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> Result<()> {
+        self.text.show_cursor()
+    }
+
+    fn get_cursor_position(&mut self) -> Result<Position> {
+        self.text.get_cursor_position()
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> Result<()> {
+        self.text.set_cursor_position(position)
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        self.buffer.reset();
+        self.text.clear()
+    }
+
+    fn size(&self) -> Result<Size> {
+        self.text.size()
+    }
+
+    fn window_size(&mut self) -> Result<WindowSize> {
+        self.text.window_size()
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Some(protocol) = self.protocol {
+            self.draw_image(protocol)?;
+        }
+        Backend::flush(&mut self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miniz_oxide::inflate::decompress_to_vec_zlib;
+
+    fn decode_base64(text: &[u8]) -> Vec<u8> {
+        let mut bits = Vec::new();
+        for c in text.iter().filter(|c| **c != b'=') {
+            let value = BASE64.iter().position(|b| b == c).expect("base64 digit");
+            bits.extend((0..6).rev().map(|i| (value >> i) & 1));
+        }
+        bits.chunks_exact(8)
+            .map(|byte| byte.iter().fold(0, |n, bit| n << 1 | *bit as u8))
+            .collect()
+    }
+
+    // Split kitty output into the control data and payload of each chunk
+    fn kitty_chunks(out: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let text = String::from_utf8(out.to_vec()).unwrap();
+        let chunks = text.strip_suffix("\x1b\\").unwrap().split("\x1b\\");
+        chunks
+            .map(|chunk| {
+                let chunk = chunk.strip_prefix("\x1b_G").unwrap();
+                let (control, payload) = chunk.split_once(';').unwrap();
+                (control.to_string(), payload.as_bytes().to_vec())
+            })
+            .collect()
+    }
+
+    // An image that barely compresses, so it takes a few chunks to send
+    fn noise(width: usize, height: usize) -> Image {
+        let mut image = Image::new(width, height);
+        let mut seed = 1u32;
+        for pixel in image.pixels.iter_mut() {
+            *pixel = [0; 3].map(|_| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                (seed >> 24) as f32 / 255.0
+            });
+        }
+        image
+    }
+
+    #[test]
+    fn base64_padding() {
+        let encode = |text: &str| String::from_utf8(base64(text.as_bytes())).unwrap();
+        assert_eq!(encode(""), "");
+        assert_eq!(encode("f"), "Zg==");
+        assert_eq!(encode("fo"), "Zm8=");
+        assert_eq!(encode("foo"), "Zm9v");
+        assert_eq!(encode("foob"), "Zm9vYg==");
+        assert_eq!(decode_base64(b"Zm9vYg=="), b"foob");
+    }
+
+    #[test]
+    fn kitty_single_chunk() {
+        let mut image = Image::new(2, 1);
+        image.pixels[1] = [1.0, 0.5, 0.0];
+        let chunks = kitty_chunks(&encode_kitty(&image, 3, 4));
+        assert_eq!(chunks.len(), 1);
+        let (control, payload) = &chunks[0];
+        assert_eq!(control, "a=T,f=24,o=z,s=2,v=1,c=3,r=4,i=1,q=2,C=1,m=0");
+        let rgb = decompress_to_vec_zlib(&decode_base64(payload)).unwrap();
+        assert_eq!(rgb, [0, 0, 0, 255, 128, 0]);
+    }
+
+    #[test]
+    fn kitty_chunks_round_trip() {
+        let image = noise(64, 48);
+        let chunks = kitty_chunks(&encode_kitty(&image, 10, 5));
+        assert!(chunks.len() > 2);
+        assert!(chunks[0].0.starts_with("a=T,f=24,o=z,s=64,v=48,"));
+        let last = chunks.len() - 1;
+        let mut data = Vec::new();
+        for (i, (control, payload)) in chunks.iter().enumerate() {
+            let more = match i == last {
+                true => "m=0",
+                false => "m=1",
+            };
+            assert!(control.ends_with(more));
+            if i > 0 {
+                assert_eq!(control, more);
+            }
+            match i == last {
+                true => assert!(payload.len() <= 4096),
+                false => assert_eq!(payload.len(), 4096),
+            }
+            data.extend(payload);
+        }
+        let rgb = decompress_to_vec_zlib(&decode_base64(&data)).unwrap();
+        assert_eq!(rgb, image.rgb());
+    }
+
+    #[test]
+    fn sixel_palette_and_bands() {
+        // One colored pixel in the corner of a black image, with a second
+        // band that's only one row high
+        let mut image = Image::new(5, 7);
+        image.pixels[0] = [0.5, 0.2, 1.0];
+        let out = String::from_utf8(encode_sixel(&image)).unwrap();
+        assert_eq!(
+            out,
+            "\x1bP0;1;0q\"1;1;5;7\
+             #0;2;0;0;0#119;2;60;20;100\
+             #119@!4?$#0}!4~-\
+             #0!5@-\
+             \x1b\\"
+        );
+    }
+
+    #[test]
+    fn sixel_short_runs_are_written_out() {
+        let mut image = Image::new(7, 1);
+        image.pixels[3] = [1.0; 3];
+        let out = String::from_utf8(encode_sixel(&image)).unwrap();
+        assert!(out.ends_with("#0@@@?@@@$#215???@???-\x1b\\"));
+    }
+}
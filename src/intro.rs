@@ -0,0 +1,154 @@
+use crate::keys::{Action, Keymap};
+use crate::matrix::State;
+use crate::screen::Signals;
+use crossterm::event::{self, Event, KeyEventKind};
+use ratatui::layout::Rect;
+use ratatui::prelude::{Backend, Terminal};
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Paragraph, Wrap};
+use serde::Deserialize;
+use std::io::Result;
+use std::time::{Duration, Instant};
+use unicode_segmentation::UnicodeSegmentation;
+
+// How long the cursor stays on and off when blinking
+const BLINK: Duration = Duration::from_millis(500);
+// How long the screen stays empty between lines
+const BLANK: Duration = Duration::from_millis(400);
+// How often to look for signals while waiting
+const POLL: Duration = Duration::from_millis(100);
+
+// The lines typed out before the rain, from the `intro` section of the config
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IntroConfig {
+    pub lines: Vec<String>,
+    // Milliseconds to type each character
+    pub delay: u64,
+    // Milliseconds to leave each line up once it's typed
+    pub pause: u64,
+}
+
+impl Default for IntroConfig {
+    fn default() -> Self {
+        Self {
+            lines: [
+                "Wake up, Neo...",
+                "The Matrix has you...",
+                "Follow the white rabbit.",
+                "Knock, knock, Neo.",
+            ]
+            .map(String::from)
+            .to_vec(),
+            delay: 90,
+            pause: 2000,
+        }
+    }
+}
+
+// Why the intro stopped early
+enum Stop {
+    Skip,
+    Quit,
+}
+
+// Type out each line with a blinking cursor, clearing the screen between
+// them. Any key skips the rest, and returns false if it was the quit key.
+pub fn play<B: Backend>(
+    terminal: &mut Terminal<B>,
+    config: &IntroConfig,
+    state: &State,
+    keymap: &Keymap,
+    signals: &Signals,
+) -> Result<bool> {
+    let style = match state.no_color {
+        true => Style::default(),
+        false => Style::default().fg(state.color_depth.reduce(Color::LightGreen)),
+    };
+    let delay = Duration::from_millis(config.delay);
+    let pause = Duration::from_millis(config.pause);
+
+    for line in config.lines.iter() {
+        let graphemes = line.graphemes(true).collect::<Vec<&str>>();
+        let typing = delay * graphemes.len() as u32;
+        let start = Instant::now();
+        loop {
+            let elapsed = start.elapsed();
+            if elapsed >= typing + pause {
+                break;
+            }
+            let (typed, cursor, next) = match elapsed.as_nanos().checked_div(delay.as_nanos()) {
+                // Still typing, with the cursor on after the last character
+                Some(typed) if elapsed < typing => {
+                    let typed = typed as u32 + 1;
+                    (typed as usize, true, start + delay * typed)
+                }
+                // Typed out, with the cursor blinking
+                _ => {
+                    let blinks = ((elapsed - typing).as_millis() / BLINK.as_millis()) as u32;
+                    let next = start + typing + BLINK * (blinks + 1);
+                    (
+                        graphemes.len(),
+                        blinks % 2 == 0,
+                        next.min(start + typing + pause),
+                    )
+                }
+            };
+            let mut text = graphemes[..typed].concat();
+            if cursor {
+                text.push('█');
+            }
+            terminal.draw(|frame| {
+                let area = frame.area();
+                let area = Rect::new(
+                    area.x + 1,
+                    area.y + 1,
+                    area.width.saturating_sub(2),
+                    area.height.saturating_sub(1),
+                )
+                .intersection(area);
+                frame.render_widget(
+                    Paragraph::new(text).style(style).wrap(Wrap { trim: false }),
+                    area,
+                );
+            })?;
+            match wait(next, keymap, signals)? {
+                Some(Stop::Skip) => return Ok(true),
+                Some(Stop::Quit) => return Ok(false),
+                None => {}
+            }
+        }
+        terminal.draw(|_| {})?;
+        match wait(Instant::now() + BLANK, keymap, signals)? {
+            Some(Stop::Skip) => return Ok(true),
+            Some(Stop::Quit) => return Ok(false),
+            None => {}
+        }
+    }
+    Ok(true)
+}
+
+// Wait until the time, unless a key is pressed or a signal says to quit
+fn wait(until: Instant, keymap: &Keymap, signals: &Signals) -> Result<Option<Stop>> {
+    loop {
+        if signals.quit() {
+            return Ok(Some(Stop::Quit));
+        }
+        let timeout = until.saturating_duration_since(Instant::now());
+        if timeout.is_zero() {
+            return Ok(None);
+        }
+        if !event::poll(timeout.min(POLL))? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            return match keymap.action(&key) {
+                Some(Action::Quit) => Ok(Some(Stop::Quit)),
+                _ => Ok(Some(Stop::Skip)),
+            };
+        }
+    }
+}
@@ -0,0 +1,421 @@
+use crate::matrix::Direction;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{Error, ErrorKind, Result};
+
+// Everything a key can be bound to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Quit,
+    Suspend,
+    Help,
+    // Move between panes when the screen is split
+    FocusNext,
+    FocusPrevious,
+    CycleColor,
+    ToggleBold,
+    // Speed from 1 to 10
+    SetSpeed(u8),
+    SetDirection(Direction),
+}
+
+impl Action {
+    // Written the same way as in the config file
+    pub fn parse(action: &str) -> Option<Self> {
+        let mut words = action.split_whitespace();
+        let action = match (words.next()?, words.next()) {
+            ("quit", None) => Action::Quit,
+            ("suspend", None) => Action::Suspend,
+            ("help", None) => Action::Help,
+            ("focus-next", None) => Action::FocusNext,
+            ("focus-previous", None) => Action::FocusPrevious,
+            ("cycle-color", None) => Action::CycleColor,
+            ("toggle-bold", None) => Action::ToggleBold,
+            ("speed", Some(speed)) => match speed.parse() {
+                Ok(speed @ 1..=10) => Action::SetSpeed(speed),
+                _ => return None,
+            },
+            ("direction", Some(direction)) => {
+                Action::SetDirection(Direction::from_name(direction)?)
+            }
+            _ => return None,
+        };
+        match words.next() {
+            Some(_) => None,
+            None => Some(action),
+        }
+    }
+
+    // What the action does, worded to follow "use `key` to"
+    fn summary(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Suspend => "suspend",
+            Action::Help => "show the keys",
+            Action::FocusNext | Action::FocusPrevious => "move between panes",
+            Action::CycleColor => "cycle colors",
+            Action::ToggleBold => "toggle bold",
+            Action::SetSpeed(_) => "change speed",
+            Action::SetDirection(_) => "change direction",
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::Quit => write!(f, "quit"),
+            Action::Suspend => write!(f, "suspend"),
+            Action::Help => write!(f, "help"),
+            Action::FocusNext => write!(f, "focus-next"),
+            Action::FocusPrevious => write!(f, "focus-previous"),
+            Action::CycleColor => write!(f, "cycle-color"),
+            Action::ToggleBold => write!(f, "toggle-bold"),
+            Action::SetSpeed(speed) => write!(f, "speed {speed}"),
+            Action::SetDirection(direction) => write!(f, "direction {}", direction.name()),
+        }
+    }
+}
+
+// A key along with the modifiers held down, like `ctrl+c`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn parse(key: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = key;
+        // Split on the `+` before the key so `ctrl++` still means ctrl and `+`
+        while let Some((modifier, key)) = rest.split_once('+') {
+            if key.is_empty() {
+                break;
+            }
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return None,
+            };
+            rest = key;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next()?, chars.next()) {
+            (c, None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (c, None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "esc" => KeyCode::Esc,
+                "enter" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+            },
+        };
+        Some(Self::new(code, modifiers))
+    }
+
+    fn new(code: KeyCode, mut modifiers: KeyModifiers) -> Self {
+        // Shift is already part of the char, so `A` and `shift+a` can't differ
+        if let KeyCode::Char(_) = code {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code, modifiers }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::BackTab => write!(f, "backtab"),
+            KeyCode::Backspace => write!(f, "backspace"),
+            KeyCode::Delete => write!(f, "delete"),
+            KeyCode::Insert => write!(f, "insert"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            KeyCode::Up => write!(f, "up"),
+            KeyCode::Down => write!(f, "down"),
+            KeyCode::Left => write!(f, "left"),
+            KeyCode::Right => write!(f, "right"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+const DEFAULT_KEYS: &[(&str, &str)] = &[
+    ("q", "quit"),
+    ("esc", "quit"),
+    ("ctrl+c", "quit"),
+    ("ctrl+z", "suspend"),
+    ("?", "help"),
+    ("tab", "focus-next"),
+    ("backtab", "focus-previous"),
+    ("c", "cycle-color"),
+    ("b", "toggle-bold"),
+    ("1", "speed 1"),
+    ("2", "speed 2"),
+    ("3", "speed 3"),
+    ("4", "speed 4"),
+    ("5", "speed 5"),
+    ("6", "speed 6"),
+    ("7", "speed 7"),
+    ("8", "speed 8"),
+    ("9", "speed 9"),
+    ("0", "speed 10"),
+    ("up", "direction up"),
+    ("down", "direction down"),
+    ("left", "direction left"),
+    ("right", "direction right"),
+];
+
+// Which action each key is bound to, in the order they are listed in help
+pub struct Keymap {
+    bindings: Vec<(Key, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).expect("default keys are valid")
+    }
+}
+
+impl Keymap {
+    // Start from the default keys and apply the ones from the config on top.
+    // Binding a key to `none` removes it.
+    pub fn new(keys: &BTreeMap<String, String>) -> Result<Self> {
+        let invalid = |msg: String| Error::new(ErrorKind::InvalidInput, msg);
+        let mut keymap = Self {
+            bindings: Vec::new(),
+        };
+        let defaults = DEFAULT_KEYS
+            .iter()
+            .map(|(key, action)| (key.to_string(), action.to_string()));
+        let configured = keys
+            .iter()
+            .map(|(key, action)| (key.clone(), action.clone()));
+        for (name, action) in defaults.chain(configured) {
+            let key = Key::parse(&name).ok_or_else(|| invalid(format!("unknown key `{name}`")))?;
+            let bound = keymap.bindings.iter().position(|(bound, _)| *bound == key);
+            if action.trim() == "none" {
+                if let Some(i) = bound {
+                    keymap.bindings.remove(i);
+                }
+                continue;
+            }
+            let action = Action::parse(&action)
+                .ok_or_else(|| invalid(format!("unknown action `{action}` for key `{name}`")))?;
+            // Rebinding a key keeps its place in the help
+            match bound {
+                Some(i) => keymap.bindings[i] = (key, action),
+                None => keymap.bindings.push((key, action)),
+            }
+        }
+        Ok(keymap)
+    }
+
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        let key = Key::new(event.code, event.modifiers);
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == key)
+            .map(|(_, action)| *action)
+    }
+
+    // Every action along with all of the keys bound to it
+    fn actions(&self) -> Vec<(Action, Vec<Key>)> {
+        let mut actions: Vec<(Action, Vec<Key>)> = Vec::new();
+        for (key, action) in self.bindings.iter() {
+            match actions.iter_mut().find(|(bound, _)| bound == action) {
+                Some((_, keys)) => keys.push(*key),
+                None => actions.push((*action, vec![*key])),
+            }
+        }
+        actions
+    }
+
+    // A sentence for `--help`, like "Use `c` to cycle colors and `q` to quit."
+    pub fn about(&self) -> String {
+        let mut summaries: Vec<(&str, Vec<String>)> = Vec::new();
+        for (key, action) in self.bindings.iter() {
+            let key = format!("`{key}`");
+            match summaries
+                .iter_mut()
+                .find(|(summary, _)| *summary == action.summary())
+            {
+                Some((_, keys)) => keys.push(key),
+                None => summaries.push((action.summary(), vec![key])),
+            }
+        }
+        let mut parts: Vec<String> = summaries
+            .into_iter()
+            .map(|(summary, keys)| format!("{} to {summary}", keys.join("/")))
+            .collect();
+
+        let mut about = String::from("Creates the matrix in the terminal.");
+        if let Some(last) = parts.pop() {
+            match parts.is_empty() {
+                true => about.push_str(&format!(" Use {last}.")),
+                false => about.push_str(&format!(" Use {}, and {last}.", parts.join(", "))),
+            }
+        }
+        about
+    }
+
+    // List the keys in a box in the middle of the screen
+    pub fn draw_help(&self, frame: &mut Frame) {
+        let actions = self.actions();
+        let keys: Vec<String> = actions
+            .iter()
+            .map(|(_, keys)| {
+                keys.iter()
+                    .map(|key| key.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            })
+            .collect();
+        let key_width = keys.iter().map(|keys| keys.len()).max().unwrap_or(0);
+        let lines: Vec<Line> = actions
+            .iter()
+            .zip(keys.iter())
+            .map(|((action, _), keys)| Line::from(format!("{keys:<key_width$}  {action}")))
+            .collect();
+        let width = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+
+        // Leave room for the border on every side
+        let area = frame.area();
+        let width = (width as u16 + 4).min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let help = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        frame.render_widget(Clear, help);
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Keys ")),
+            help,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keymap(keys: &[(&str, &str)]) -> Result<Keymap> {
+        let keys = keys
+            .iter()
+            .map(|(key, action)| (key.to_string(), action.to_string()))
+            .collect();
+        Keymap::new(&keys)
+    }
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn keys_with_modifiers() {
+        let key = |code, modifiers| Some(Key::new(code, modifiers));
+        assert_eq!(
+            Key::parse("ctrl+c"),
+            key(KeyCode::Char('c'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            Key::parse("Alt+Shift+up"),
+            key(KeyCode::Up, KeyModifiers::ALT | KeyModifiers::SHIFT)
+        );
+        assert_eq!(
+            Key::parse("ctrl++"),
+            key(KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+        // Shift is part of the char
+        assert_eq!(Key::parse("shift+j"), Key::parse("J"));
+    }
+
+    #[test]
+    fn named_keys() {
+        let key = |code| Some(Key::new(code, KeyModifiers::NONE));
+        assert_eq!(Key::parse("esc"), key(KeyCode::Esc));
+        assert_eq!(Key::parse("Space"), key(KeyCode::Char(' ')));
+        assert_eq!(Key::parse("pagedown"), key(KeyCode::PageDown));
+        assert_eq!(Key::parse("f5"), key(KeyCode::F(5)));
+    }
+
+    #[test]
+    fn invalid_keys() {
+        for name in ["", "escape", "fx", "hyper+c", "ctrl+"] {
+            assert_eq!(Key::parse(name), None, "{name}");
+        }
+        let error = keymap(&[("escape", "quit")]).err().unwrap();
+        assert_eq!(error.to_string(), "unknown key `escape`");
+        let error = keymap(&[("x", "explode")]).err().unwrap();
+        assert_eq!(error.to_string(), "unknown action `explode` for key `x`");
+    }
+
+    #[test]
+    fn same_key_bound_twice() {
+        // Both names are the same key, so the one applied last wins
+        let keymap = keymap(&[("CTRL+x", "help"), ("ctrl+x", "toggle-bold")]).unwrap();
+        let key = Key::new(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        let bound = keymap.bindings.iter().filter(|(bound, _)| *bound == key);
+        assert_eq!(bound.count(), 1);
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('x'), KeyModifiers::CONTROL)),
+            Some(Action::ToggleBold)
+        );
+    }
+
+    #[test]
+    fn config_overrides_defaults() {
+        let keymap = keymap(&[("q", "help"), ("x", "quit"), ("esc", "none")]).unwrap();
+        let action = |code| keymap.action(&press(code, KeyModifiers::NONE));
+        assert_eq!(action(KeyCode::Char('q')), Some(Action::Help));
+        assert_eq!(action(KeyCode::Char('x')), Some(Action::Quit));
+        assert_eq!(action(KeyCode::Esc), None);
+        // Untouched defaults are still there, and a rebound key keeps its place
+        assert_eq!(action(KeyCode::Char('c')), Some(Action::CycleColor));
+        assert_eq!(keymap.bindings[0].1, Action::Help);
+    }
+}
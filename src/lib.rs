@@ -0,0 +1,18 @@
+pub mod bench;
+pub mod colors;
+pub mod config;
+pub mod feed;
+pub mod font;
+pub mod glyphs;
+pub mod graphics;
+pub mod intro;
+pub mod keys;
+pub mod logging;
+pub mod matrix;
+pub mod palettes;
+pub mod pane;
+pub mod react;
+pub mod screen;
+pub mod serve;
+pub mod tail;
+pub mod telnet;
@@ -0,0 +1,26 @@
+use log::LevelFilter;
+use log4rs::append::file::FileAppender;
+use log4rs::config::{Appender, Config, Root};
+use log4rs::encode::pattern::PatternEncoder;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+
+// Log to a file, since anything written to the terminal would end up in the
+// middle of the rain. Warnings are always logged, and each `-v` adds a level.
+pub fn init(path: &Path, verbose: u8) -> Result<()> {
+    let level = match verbose {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    let file = FileAppender::builder()
+        .encoder(Box::new(PatternEncoder::new("{d} {l} {t} - {m}{n}")))
+        .build(path)?;
+    let config = Config::builder()
+        .appender(Appender::builder().build("file", Box::new(file)))
+        .build(Root::builder().appender("file").build(level))
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))?;
+    log4rs::init_config(config).map_err(Error::other)?;
+    Ok(())
+}
@@ -1,31 +1,77 @@
-use clap::Parser;
-use crossterm::{
-    event::{self, KeyCode, KeyEventKind},
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-    ExecutableCommand,
-};
-use matrix::{Direction, LineState, State};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use crossterm::event::{self, KeyEventKind};
+use log::{debug, error, info, trace, warn};
 use rand::{thread_rng, Rng};
 use ratatui::{
     layout::Rect,
-    prelude::{CrosstermBackend, Terminal},
+    prelude::{Backend, Terminal},
+    style::Color,
+    widgets::{Block, Borders},
 };
-use std::io::{stdout, Result};
+use rjmatrix::bench;
+use rjmatrix::colors::ColorDepth;
+use rjmatrix::config::Config;
+use rjmatrix::feed::Feed;
+use rjmatrix::glyphs::Glyphs;
+use rjmatrix::graphics::{GraphicsBackend, Protocol};
+use rjmatrix::intro;
+use rjmatrix::keys::{Action, Keymap};
+use rjmatrix::logging;
+use rjmatrix::matrix::{self, Direction, Layer, State};
+use rjmatrix::palettes;
+use rjmatrix::pane::{Pane, PaneLayout};
+use rjmatrix::react::{React, Reactor};
+use rjmatrix::screen::{Screen, Signals};
+use rjmatrix::serve;
+use rjmatrix::tail::Tail;
+use std::env;
+use std::io::{Error, ErrorKind, Result};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
+// The about text comes from the keymap, so it always matches the keys
 #[derive(Parser)]
-#[command(
-    about = "Creates the matrix in the terminal. Use `c` to cycle colors, `0-9` to change speed, arrow keys to change direction, `b` to toggle bold, and `q` to quit."
-)]
 struct Cli {
     #[arg(
         short,
         long,
         value_name = "COLOR",
-        help = "Available colors: blue, cyan, red, purple, yellow, green, rainbow"
+        help = "Available colors: blue, cyan, red, purple, yellow, green, high-contrast, deuteranopia, protanopia, tritanopia, rainbow, flicker, cycle, spectrum, wave"
     )]
     color: Option<String>,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 10.0,
+        help = "How long the cycle, spectrum and wave colors take to go around once"
+    )]
+    period: f32,
+    #[arg(
+        long,
+        value_name = "SATURATION",
+        default_value_t = 1.0,
+        help = "Saturation of the cycle, spectrum and wave colors: 0.0-1.0"
+    )]
+    saturation: f32,
+    #[arg(
+        long,
+        value_name = "COLOR",
+        help = "Background color, either a color name, black or a hex code like `#001100`"
+    )]
+    background: Option<String>,
+    #[arg(
+        long,
+        help = "Tint the background behind each character with its color. Defaults the background to black"
+    )]
+    tint: bool,
+    #[arg(
+        long,
+        value_name = "DEPTH",
+        help = "How many colors the terminal can show: truecolor, 256, 16, 8 or none. Detected by default"
+    )]
+    color_depth: Option<String>,
     #[arg(short, long, value_name = "SPEED", help = "Speed: 1-10")]
-    speed: Option<i8>,
+    speed: Option<u8>,
     #[arg(
         short,
         long,
@@ -33,193 +79,455 @@ struct Cli {
         help = "Direction: up, down, left, or right"
     )]
     direction: Option<String>,
+    #[arg(
+        long,
+        value_name = "LAYERS",
+        default_value_t = 1,
+        help = "Layers of rain at different depths: 1-3"
+    )]
+    layers: usize,
+    #[arg(
+        long,
+        help = "Move the rain by half cells at a time so it looks smoother at slow speeds"
+    )]
+    smooth: bool,
+    #[arg(
+        long,
+        help = "Keep the rain slow and sparse in a steady color, for anyone who finds the motion hard to look at"
+    )]
+    reduced_motion: bool,
     #[arg(short, long, value_name = "BOLD", help = "Make the text bold")]
     bold: bool,
+    #[arg(
+        long,
+        value_name = "FILE",
+        action = clap::ArgAction::Append,
+        help = "Read the characters from a file instead, or `-` for stdin. Repeat for more files"
+    )]
+    source: Option<Vec<String>>,
+    #[arg(
+        long,
+        value_name = "LAYOUT",
+        help = "Split the screen into panes, e.g. `2x2` for columns by rows, or `h:1,2` for columns of 1 and 2 panes. Tab moves between them"
+    )]
+    layout: Option<String>,
+    #[arg(
+        long,
+        value_name = "MODE",
+        help = "Draw the rain as glowing images: auto, kitty or sixel. Auto falls back to text when the terminal can't show images"
+    )]
+    graphics: Option<String>,
+    #[arg(
+        long,
+        value_name = "SOURCE",
+        help = "Make the rain follow the load: cpu, cpu-per-core, or net[:IFACE] for received traffic falling and sent traffic rising"
+    )]
+    react: Option<String>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Steer the rain with numbers or `key=value` lines from a file, FIFO or `-` for stdin, mapped in the config"
+    )]
+    feed: Option<String>,
+    #[arg(
+        long,
+        help = "Type out a few lines before the rain starts, which can be set in the config. Any key skips them"
+    )]
+    intro: bool,
+    #[arg(long, value_name = "FILE", help = "Log warnings to a file")]
+    log: Option<PathBuf>,
+    #[arg(
+        short,
+        long,
+        action = clap::ArgAction::Count,
+        requires = "log",
+        help = "Log more to the `--log` file, up to `-vvv`"
+    )]
+    verbose: u8,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "Follow files and drop each new line into the matrix as a stream")]
+    Tail {
+        #[arg(value_name = "FILE", required = true)]
+        files: Vec<String>,
+        #[arg(
+            long,
+            value_name = "REGEX=COLOR",
+            help = "Color lines matching the regex, e.g. `ERROR=red`. Can be repeated"
+        )]
+        highlight: Vec<String>,
+    },
+    #[command(about = "Measure how fast the matrix updates and draws on this machine")]
+    Bench {
+        #[arg(long, value_name = "WxH", default_value = "200x60")]
+        size: String,
+        #[arg(long, value_name = "N", default_value_t = 1000)]
+        frames: u64,
+    },
+    #[command(about = "Show the high contrast and color blind palettes side by side")]
+    Preview,
+    #[command(about = "Serve the matrix to telnet clients, each with their own rain")]
+    Serve {
+        #[arg(long, value_name = "PORT", default_value_t = 2323)]
+        port: u16,
+        #[arg(long, value_name = "ADDRESS", default_value = "0.0.0.0")]
+        bind: String,
+    },
 }
 
-mod matrix;
+// How long the focused pane is outlined after moving to it
+const FOCUS_OUTLINE: Duration = Duration::from_millis(800);
+// How often to warn about frames taking much longer than a tick
+const SLOW_FRAME_WARNING: Duration = Duration::from_secs(10);
+// How often to warn about the load not being read
+const SAMPLE_WARNING: Duration = Duration::from_secs(60);
 
 fn main() -> Result<()> {
-    // log4rs::init_file("config/log4rs.yaml", Default::default()).unwrap();
-    let cli = Cli::parse();
-    // Poll duration determines how fast the matrix falls
-    let speed = match cli.speed {
-        Some(s) => match s {
-            1 => 120,
-            2 => 100,
-            3 => 80,
-            4 => 60,
-            5 => 50,
-            6 => 40,
-            7 => 30,
-            8 => 20,
-            9 => 10,
-            10 => 5,
-            _ => 60,
-        },
-        None => 60,
+    // Hold on to config errors until logging is set up so they can be logged
+    let config = Config::load().and_then(|config| {
+        let keymap = Keymap::new(&config.keys)?;
+        Ok((config, keymap))
+    });
+    let about = match &config {
+        Ok((_, keymap)) => keymap.about(),
+        Err(_) => Keymap::default().about(),
     };
+    let matches = Cli::command().about(about).get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if let Some(path) = &cli.log {
+        logging::init(path, cli.verbose)?;
+    }
+    let (config, keymap) = config.map_err(|e| {
+        error!("Config: {e}");
+        e
+    })?;
+    // Poll duration determines how fast the matrix falls
+    let speed = matrix::speed_millis(cli.speed.unwrap_or(4));
 
-    let direction = match cli.direction {
-        Some(d) => match d.to_lowercase().as_str() {
-            "up" => Direction::Up,
-            "right" => Direction::Right,
-            "left" => Direction::Left,
-            _ => Direction::Down,
-        },
+    let direction = match cli.direction.as_deref() {
+        Some(name) => Direction::from_name(name).unwrap_or_else(|| {
+            warn!("Unknown direction `{name}`, falling down");
+            Direction::Down
+        }),
         None => Direction::Down,
     };
     let bold = cli.bold;
-    let mut state = if let Some(color) = cli.color.as_deref() {
-        match color.to_lowercase().as_str() {
-            "blue" => State {
-                color: color.to_string(),
-                speed,
-                direction,
-                bold,
-            },
-            "cyan" => State {
-                color: color.to_string(),
-                speed,
-                direction,
-                bold,
-            },
-            "red" => State {
-                color: color.to_string(),
-                speed,
-                direction,
-                bold,
-            },
-            "purple" => State {
-                color: color.to_string(),
-                speed,
-                direction,
-                bold,
-            },
-            "yellow" => State {
-                color: color.to_string(),
-                speed,
-                direction,
-                bold,
-            },
-            "rainbow" => State {
-                color: color.to_string(),
-                speed,
-                direction,
-                bold,
-            },
-            _ => State {
-                color: "green".to_string(),
-                speed,
-                direction,
-                bold,
-            },
+    let mut tail = match &cli.command {
+        Some(Command::Tail { files, highlight }) => Some(Tail::new(files, highlight)?),
+        _ => None,
+    };
+    let glyphs = match (&tail, cli.source) {
+        (Some(_), _) => Glyphs::Launched,
+        (None, Some(paths)) => Glyphs::from_sources(&paths, cli.smooth)?,
+        (None, None) => Glyphs::Random,
+    };
+    let color = match cli.color.map(|color| color.to_lowercase()) {
+        Some(color) if matrix::COLORS.contains(&color.as_str()) => color,
+        Some(color) => {
+            warn!("Unknown color `{color}`, using green");
+            "green".to_string()
         }
-    } else {
-        State {
-            color: "green".to_string(),
-            speed,
-            direction,
-            bold,
+        None => "green".to_string(),
+    };
+    let color = match cli.reduced_motion && matrix::MOVING_COLORS.contains(&color.as_str()) {
+        true => {
+            warn!("`{color}` moves too much for --reduced-motion, using green");
+            "green".to_string()
         }
+        false => color,
+    };
+    let background = match cli.background.as_deref() {
+        Some(background) => Some(matrix::parse_color(background).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("unknown background color `{background}`"),
+            )
+        })?),
+        // High contrast is meant to be on black
+        None if cli.tint || color == "high-contrast" => Some(Color::Black),
+        None => None,
+    };
+    let color_depth = match cli.color_depth.as_deref() {
+        Some(depth) => Some(ColorDepth::parse(depth)?),
+        None => None,
+    };
+    // Serve clients have their own terminals, so only bring the colors down
+    // for them when asked
+    let depth = match &cli.command {
+        Some(Command::Serve { .. }) => color_depth.unwrap_or_default(),
+        _ => color_depth.unwrap_or_else(ColorDepth::detect),
+    };
+    info!("Color depth: {}", depth.name());
+    let state = State {
+        color,
+        speed,
+        direction,
+        bold,
+        glyphs,
+        period: cli.period.max(0.1),
+        saturation: cli.saturation.clamp(0.0, 1.0),
+        start: Instant::now(),
+        background,
+        tint: cli.tint,
+        smooth: cli.smooth,
+        hue: None,
+        // Any value but an empty one turns colors off, see no-color.org
+        no_color: env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+            || depth == ColorDepth::Mono,
+        reduced_motion: cli.reduced_motion,
+        color_depth: depth,
+    };
+    let react = match cli.react.as_deref() {
+        Some(react) => Some(React::parse(react)?),
+        None => None,
+    };
+    let preview = matches!(cli.command, Some(Command::Preview));
+    let layout = match (cli.layout.as_deref(), &react) {
+        _ if preview => palettes::preview_layout()?,
+        (Some(layout), _) => PaneLayout::parse(layout)?,
+        (None, Some(react)) => react.layout(),
+        (None, None) => PaneLayout::default(),
+    };
+    if let Some(Command::Bench { size, frames }) = &cli.command {
+        let (width, height) = bench::parse_size(size)?;
+        let mut layers = Layer::create_layers(cli.layers);
+        return bench::run(width, height, *frames, &mut layers, &state);
+    }
+    if let Some(Command::Serve { port, bind }) = &cli.command {
+        return serve::run(*port, bind, state, cli.layers, keymap);
+    }
+
+    let protocol = match cli.graphics.as_deref() {
+        Some(mode) => Protocol::choose(mode)?,
+        None => None,
+    };
+    let mut reactor = match &react {
+        Some(react) => Some(Reactor::new(react.clone())?),
+        None => None,
+    };
+    let mut feed = match cli.feed.as_deref() {
+        Some(path) => Some(Feed::new(path, config.feed)?),
+        None => None,
     };
-    // Initialize ratatui and get terminal size
-    stdout().execute(EnterAlternateScreen)?;
-    enable_raw_mode()?;
-    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
-    let mut matrix: Vec<LineState> = Vec::new();
-    matrix::create_matrix(&mut matrix, &mut terminal, &state)?;
+
+    // Initialize ratatui and get terminal size. The screen is restored when
+    // it goes out of scope, however main ends.
+    let signals = Signals::new()?;
+    let screen = Screen::new()?;
+    let mut terminal = Terminal::new(GraphicsBackend::new(protocol, state.color_depth))?;
+    if cli.intro && !intro::play(&mut terminal, &config.intro, &state, &keymap, &signals)? {
+        info!("Quitting during the intro");
+        return Ok(());
+    }
+    let size = terminal.size()?;
+    let area = Rect::new(0, 0, size.width, size.height);
+    let mut panes: Vec<Pane> = layout
+        .areas(area)
+        .into_iter()
+        .enumerate()
+        .map(|(i, area)| {
+            let mut state = state.clone();
+            // Some things the rain follows decide which way it goes
+            if let Some(direction) = react.as_ref().and_then(|react| react.direction(i)) {
+                state.direction = direction;
+            }
+            if let Some(color) = palettes::PREVIEW.get(i).filter(|_| preview) {
+                state.color = color.to_string();
+                if *color == "high-contrast" {
+                    state.background = state.background.or(Some(Color::Black));
+                }
+            }
+            Pane::new(state, cli.layers, area)
+        })
+        .collect();
+    let mut focus = 0;
+    // The focused pane is outlined for a moment after moving to it
+    let mut focused_at: Option<Instant> = None;
+    let mut help = false;
+    // Slow frames since the last warning about them
+    let mut slow_frames = 0;
+    let mut warned_at: Option<Instant> = None;
+    // Failed reads of the load since the last warning about them
+    let mut failed_samples = 0;
+    let mut sample_warned_at: Option<Instant> = None;
 
     loop {
-        // Only print matrix every other column
-        // Looks better than using every column
-        for line in matrix.iter_mut() {
-            line.update_line();
+        let frame_start = Instant::now();
+        if let Some(reactor) = reactor
+            .as_mut()
+            .filter(|reactor| reactor.is_due(frame_start))
+        {
+            // A read can fail for a moment, or an interface can go away, and
+            // the rain keeps going with the last load either way
+            if let Err(e) = reactor.sample(frame_start) {
+                failed_samples += 1;
+                if sample_warned_at.map_or(true, |at| at.elapsed() >= SAMPLE_WARNING) {
+                    warn!(
+                        "Couldn't read the load, keeping the last levels: {e}, \
+                         with {failed_samples} failed reads since the last warning"
+                    );
+                    failed_samples = 0;
+                    sample_warned_at = Some(Instant::now());
+                }
+            }
+            reactor.apply(&mut panes);
+        }
+        if let Some(feed) = feed.as_mut().filter(|feed| feed.is_due(frame_start)) {
+            feed.update(frame_start);
+            feed.apply(&mut panes);
+        }
+        for (i, pane) in panes.iter_mut().enumerate() {
+            if !pane.is_due(frame_start) {
+                continue;
+            }
+            if let Some(tail) = tail.as_mut().filter(|_| i == focus) {
+                // New lines always fall in the nearest layer of the focused pane
+                if let Some(layer) = pane.layers.last_mut() {
+                    tail.launch(&mut layer.matrix, &pane.state)?;
+                }
+            }
+            pane.update(frame_start);
         }
 
         // Draw the matrix after updating all lines
+        let outline = focused_at.is_some_and(|at| at.elapsed() < FOCUS_OUTLINE);
         terminal.draw(|frame| {
-            let area = Rect::new(0, 0, frame.area().width, frame.area().height);
-            if state.direction == Direction::Up || state.direction == Direction::Down {
-                // Get the state of every other column
-                for (i, col) in area.columns().enumerate().step_by(2) {
-                    matrix::process_matrix_cols(i, col, frame, &mut matrix, &state);
-                }
-            } else {
-                // Get the state of every other row
-                for (i, row) in area.rows().enumerate() {
-                    matrix::process_matrix_rows(i, row, frame, &mut matrix, &state);
-                }
+            for pane in panes.iter() {
+                pane.draw(frame);
+            }
+            if preview {
+                palettes::draw_labels(frame, &panes);
+            }
+            if outline {
+                frame.render_widget(Block::default().borders(Borders::ALL), panes[focus].area);
+            }
+            if help {
+                keymap.draw_help(frame);
             }
         })?;
 
-        if event::poll(std::time::Duration::from_millis(state.speed))? {
+        let frame_time = frame_start.elapsed();
+        let tick_time = panes
+            .iter()
+            .map(|pane| pane.tick_time())
+            .min()
+            .unwrap_or_default();
+        if frame_time > tick_time {
+            trace!("Frame took {frame_time:?}, longer than the {tick_time:?} tick");
+        }
+        // Only warn about frames well over a tick, and not on every one
+        if frame_time > tick_time * 2 {
+            slow_frames += 1;
+            if warned_at.map_or(true, |at| at.elapsed() >= SLOW_FRAME_WARNING) {
+                warn!(
+                    "Frame took {frame_time:?}, over twice the {tick_time:?} tick, \
+                     with {slow_frames} slow frames since the last warning"
+                );
+                slow_frames = 0;
+                warned_at = Some(Instant::now());
+            }
+        }
+        // Wait until the next pane needs to move
+        let next = panes
+            .iter()
+            .map(|pane| pane.next())
+            .chain(reactor.as_ref().map(|reactor| reactor.next()))
+            .chain(feed.as_ref().map(|feed| feed.next()))
+            .min();
+        let timeout = next.map_or(tick_time, |next| {
+            next.saturating_duration_since(Instant::now())
+        });
+        if event::poll(timeout)? {
+            let pane = &mut panes[focus];
             match event::read()? {
-                event::Event::Resize(_, _) => {
+                event::Event::Resize(width, height) => {
+                    info!("Resized to {width}x{height}");
                     terminal.autoresize()?;
-                    matrix::create_matrix(&mut matrix, &mut terminal, &state)?;
+                    resize(&mut panes, &layout, Rect::new(0, 0, width, height));
                 }
-                event::Event::Key(key) => {
-                    if key.kind == KeyEventKind::Press {
-                        match key.code {
-                            KeyCode::Char('q') => break,
-                            KeyCode::Char('b') => state.bold = !state.bold,
-                            KeyCode::Char('c') => {
-                                let mut rng = thread_rng();
-                                let mut colors: Vec<&str> = vec![
-                                    "blue", "cyan", "red", "purple", "yellow", "green", "rainbow",
-                                ];
-                                colors = colors
-                                    .into_iter()
-                                    .filter(|color| color != &state.color.as_str())
-                                    .collect::<Vec<&str>>();
-                                let index = rng.gen_range(0..=colors.len() - 1);
-                                state.color = colors[index].to_string();
-                            }
-                            KeyCode::Char('1') => state.speed = 120,
-                            KeyCode::Char('2') => state.speed = 100,
-                            KeyCode::Char('3') => state.speed = 80,
-                            KeyCode::Char('4') => state.speed = 60,
-                            KeyCode::Char('5') => state.speed = 50,
-                            KeyCode::Char('6') => state.speed = 40,
-                            KeyCode::Char('7') => state.speed = 30,
-                            KeyCode::Char('8') => state.speed = 20,
-                            KeyCode::Char('9') => state.speed = 10,
-                            KeyCode::Char('0') => state.speed = 5,
-                            KeyCode::Up => {
-                                if state.direction != Direction::Up {
-                                    state.direction = Direction::Up;
-                                    matrix::create_matrix(&mut matrix, &mut terminal, &state)?;
-                                }
-                            }
-                            KeyCode::Down => {
-                                if state.direction != Direction::Down {
-                                    state.direction = Direction::Down;
-                                    matrix::create_matrix(&mut matrix, &mut terminal, &state)?;
-                                }
-                            }
-                            KeyCode::Left => {
-                                if state.direction != Direction::Left {
-                                    state.direction = Direction::Left;
-                                    matrix::create_matrix(&mut matrix, &mut terminal, &state)?;
-                                }
-                            }
-                            KeyCode::Right => {
-                                if state.direction != Direction::Right {
-                                    state.direction = Direction::Right;
-                                    matrix::create_matrix(&mut matrix, &mut terminal, &state)?;
-                                }
-                            }
-                            _ => {}
+                event::Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    match keymap.action(&key) {
+                        Some(Action::Quit) => break,
+                        Some(Action::Suspend) => {
+                            info!("Suspending");
+                            screen.suspend(&signals)?;
+                            redraw(&mut panes, &layout, &mut terminal)?;
+                        }
+                        Some(Action::Help) => help = !help,
+                        Some(Action::FocusNext) => {
+                            focus = (focus + 1) % panes.len();
+                            focused_at = Some(Instant::now());
+                        }
+                        Some(Action::FocusPrevious) => {
+                            focus = (focus + panes.len() - 1) % panes.len();
+                            focused_at = Some(Instant::now());
+                        }
+                        Some(Action::ToggleBold) => pane.state.bold = !pane.state.bold,
+                        Some(Action::CycleColor) => {
+                            let mut rng = thread_rng();
+                            let colors = matrix::other_colors(&pane.state);
+                            let index = rng.gen_range(0..=colors.len() - 1);
+                            pane.state.color = colors[index].to_string();
+                            debug!("Color changed to {}", pane.state.color);
                         }
+                        Some(Action::SetSpeed(speed)) => {
+                            debug!("Speed changed to {speed}");
+                            pane.state.speed = matrix::speed_millis(speed);
+                        }
+                        Some(Action::SetDirection(direction))
+                            if pane.state.direction != direction =>
+                        {
+                            info!("Direction changed to {}", direction.name());
+                            pane.turn(direction);
+                        }
+                        _ => {}
                     }
                 }
                 _ => {}
             }
         }
+
+        if signals.quit() {
+            info!("Quitting on a signal");
+            break;
+        }
+        if signals.take_suspend() {
+            info!("Suspending on a signal");
+            screen.suspend(&signals)?;
+            redraw(&mut panes, &layout, &mut terminal)?;
+        }
+        if signals.take_resume() {
+            // Stopped by something other than us, like SIGSTOP
+            info!("Resuming after being stopped");
+            screen.resume()?;
+            redraw(&mut panes, &layout, &mut terminal)?;
+        }
     }
 
-    stdout().execute(LeaveAlternateScreen)?;
-    disable_raw_mode()?;
+    Ok(())
+}
+
+// Fit every pane to a new size of the terminal
+fn resize(panes: &mut [Pane], layout: &PaneLayout, area: Rect) {
+    for (pane, area) in panes.iter_mut().zip(layout.areas(area)) {
+        pane.resize(area);
+    }
+}
+
+// Draw everything again after coming back to a screen that may have changed
+// size or been written over while we were away
+fn redraw<B: Backend>(
+    panes: &mut [Pane],
+    layout: &PaneLayout,
+    terminal: &mut Terminal<B>,
+) -> Result<()> {
+    terminal.clear()?;
+    terminal.autoresize()?;
+    let size = terminal.size()?;
+    resize(panes, layout, Rect::new(0, 0, size.width, size.height));
     Ok(())
 }
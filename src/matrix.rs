@@ -1,21 +1,109 @@
+use crate::colors::{indexed_rgb, ColorDepth};
+use crate::glyphs::{Glyphs, Symbol};
+use crate::palettes::palette;
 use log::info;
 use rand::{thread_rng, Rng};
 use ratatui::{
-    layout::Rect,
-    prelude::{CrosstermBackend, Terminal},
-    style::Style,
-    text::{Line, Span, Text},
-    widgets::Paragraph,
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Color, Style},
     Frame,
 };
-use std::io::Result;
-use std::io::Stdout;
+use std::collections::VecDeque;
+use std::time::Instant;
 
+// How much of a symbol's color shows through the background when tinted
+const TINT: f32 = 0.15;
+
+// The slowest the rain moves with reduced motion, in milliseconds a cell
+pub const REDUCED_SPEED: u64 = 100;
+// How many times longer the gaps between streams are with reduced motion
+const REDUCED_SPARSITY: usize = 3;
+
+// Every color the matrix can be
+pub const COLORS: &[&str] = &[
+    "blue",
+    "cyan",
+    "red",
+    "purple",
+    "yellow",
+    "green",
+    "high-contrast",
+    "deuteranopia",
+    "protanopia",
+    "tritanopia",
+    "rainbow",
+    "flicker",
+    "cycle",
+    "spectrum",
+    "wave",
+];
+
+// Colors that change from stream to stream, frame to frame or over time,
+// which are left out with reduced motion
+pub const MOVING_COLORS: &[&str] = &["rainbow", "flicker", "cycle", "spectrum", "wave"];
+
+// The colors picked from at random for rainbow streams and flicker
+const RAINBOW: [Color; 6] = [
+    Color::Blue,
+    Color::Cyan,
+    Color::Red,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+];
+
+#[derive(Clone)]
 pub struct State {
     pub color: String,
     pub speed: u64,
     pub direction: Direction,
     pub bold: bool,
+    pub glyphs: Glyphs,
+    // Seconds for the animated colors to go all the way around the hues
+    pub period: f32,
+    // Saturation of the animated colors from 0.0 to 1.0
+    pub saturation: f32,
+    // When the matrix started, which the animated colors are timed from
+    pub start: Instant,
+    // Background for every cell, or the terminal's own when not set
+    pub background: Option<Color>,
+    // Faintly tint the background behind each symbol with its color
+    pub tint: bool,
+    // Simulate two cells for every one on screen so the heads move by halves
+    pub smooth: bool,
+    // A hue in degrees to color the rain with instead of the color, when
+    // something like a feed is steering it
+    pub hue: Option<f32>,
+    // Draw without any colors, for `NO_COLOR`
+    pub no_color: bool,
+    // Keep the rain slow and sparse, in steady colors
+    pub reduced_motion: bool,
+    // How many colors the terminal can show, which every color is brought
+    // down to
+    pub color_depth: ColorDepth,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            color: "green".to_string(),
+            speed: 60,
+            direction: Direction::Down,
+            bold: false,
+            glyphs: Glyphs::Random,
+            period: 10.0,
+            saturation: 1.0,
+            start: Instant::now(),
+            background: None,
+            tint: false,
+            smooth: false,
+            hue: None,
+            no_color: false,
+            reduced_motion: false,
+            color_depth: ColorDepth::TrueColor,
+        }
+    }
 }
 
 // Keep track of the state of each column individually
@@ -29,14 +117,25 @@ pub struct LineState {
     pub chars: usize,
     // How many white spaces to write
     pub whitespace: usize,
+    // Position of the next glyph when reading from a source
+    pub cursor: usize,
+    // Glyphs launched into this line that haven't been written yet
+    pub queue: VecDeque<Symbol>,
+    // Color of the stream, or the color from the state when not set
+    pub color: Option<Color>,
+    // How many times longer than normal the gaps between streams are
+    pub sparsity: usize,
+    // Left to drain away after a turn, so no new streams start
+    pub frozen: bool,
 }
 
 impl LineState {
     // Create anew line with random number of chars and whitespace to create
-    pub fn new(height: usize) -> Self {
+    pub fn new(height: usize, state: &State, sparsity: usize) -> Self {
         let mut rng = thread_rng();
 
-        let stream = match rng.gen_bool(0.02) {
+        let chance = 0.02 / (sparsity * gap_scale(state)) as f64;
+        let stream = match state.glyphs.spawns() && rng.gen_bool(chance) {
             true => Stream::On,
             false => Stream::Off,
         };
@@ -44,21 +143,98 @@ impl LineState {
         Self {
             stream,
             line: vec![Cell::Whitespace; height],
-            chars: rng.gen_range(5..height / 2),
-            whitespace: rng.gen_range(10..height),
+            chars: gen_len(&mut rng, 5, height / 2),
+            whitespace: gen_len(&mut rng, 10, height),
+            cursor: state.glyphs.start(),
+            queue: VecDeque::new(),
+            color: stream_color(&state.color),
+            sparsity,
+            frozen: false,
+        }
+    }
+
+    // Change the length of the line, keeping every cell where it is on screen
+    pub fn resize(&mut self, len: usize, direction: &Direction) {
+        let current = self.line.len();
+        if *direction == Direction::Up || *direction == Direction::Left {
+            // The start of the line is drawn at the bottom or right edge, which
+            // is the edge that moves
+            if len < current {
+                self.line.drain(..current - len);
+            } else {
+                self.line
+                    .splice(0..0, vec![Cell::Whitespace; len - current]);
+            }
+        } else {
+            self.line.resize(len, Cell::Whitespace);
+        }
+    }
+
+    // Stop the heads so nothing new is written, and the trails are erased as
+    // if every stream had just ended
+    pub fn freeze(&mut self) {
+        for cell in self.line.iter_mut() {
+            if let Cell::Sym(sym) = cell {
+                sym.white = false;
+            }
         }
+        self.stream = Stream::Off;
+        self.queue.clear();
+        self.frozen = true;
+    }
+
+    // Change how sparse the line is, shortening or stretching the gap it's
+    // already waiting out so the change shows straight away
+    pub fn set_sparsity(&mut self, sparsity: usize) {
+        let sparsity = sparsity.max(1);
+        if self.sparsity == sparsity {
+            return;
+        }
+        if matches!(self.stream, Stream::Off) {
+            self.whitespace = (self.whitespace * sparsity / self.sparsity).max(1);
+        }
+        self.sparsity = sparsity;
+    }
+
+    // Whether there is nothing left on the line, so a new stream can be launched
+    pub fn is_idle(&self) -> bool {
+        matches!(self.stream, Stream::Off)
+            && self.queue.is_empty()
+            && self
+                .line
+                .iter()
+                .all(|cell| matches!(cell, Cell::Whitespace))
+    }
+
+    // Start a stream that spells out the given glyphs
+    pub fn launch(&mut self, glyphs: Vec<Symbol>, color: Option<Color>) {
+        self.chars = glyphs.len().max(1);
+        self.queue = glyphs.into();
+        self.color = color;
+        self.stream = Stream::On;
     }
 
     // Update the line each tick
-    pub fn update_line(&mut self) {
-        const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
-                                abcdefghijklmnopqrstuvwxyz\
-                                0123456789)(}{][*&^%$#@!~";
+    pub fn update_line(&mut self, state: &State) {
+        let glyphs = &state.glyphs;
         let mut rng = thread_rng();
         let mut updated = false;
+        let mut cursor = self.cursor;
+        let color = self.color;
+        // Launched text comes first, then whatever the glyph source provides
+        let queue = &mut self.queue;
+        let mut next_glyph = || queue.pop_front().or_else(|| glyphs.next_glyph(&mut cursor));
         match self.stream {
+            // Launched text has nothing to write after it, so once it's spelled
+            // out it falls as a whole until it leaves the screen
+            Stream::Off if !glyphs.spawns() => {
+                if !self.line.is_empty() {
+                    self.line.rotate_right(1);
+                    self.line[0] = Cell::Whitespace;
+                }
+            }
             Stream::Off => {
-                let line_len = self.line.len() - 1;
+                let line_len = self.line.len().saturating_sub(1);
                 let mut iter = self.line.iter_mut();
                 loop {
                     let next = iter.next();
@@ -69,14 +245,13 @@ impl LineState {
                             }
                             Cell::Sym(sym) => match sym.white {
                                 true => {
-                                    let idx = thread_rng().gen_range(0..CHARSET.len());
-                                    let rand_char = CHARSET[idx] as char;
                                     sym.white = false;
                                     let next_cell = iter.next();
-                                    if let Some(cell) = next_cell {
+                                    if let (Some(cell), Some(value)) = (next_cell, next_glyph()) {
                                         *cell = Cell::Sym(Sym {
-                                            value: rand_char.to_string(),
+                                            value,
                                             white: true,
+                                            color: sym.color,
                                         });
                                     }
                                     updated = true;
@@ -94,14 +269,19 @@ impl LineState {
                         }
                     }
                 }
-                self.whitespace -= 1;
-                if self.whitespace == 0 {
-                    self.stream = Stream::On;
-                    self.whitespace = rng.gen_range(10..line_len);
+                // Nothing new starts while the line drains away
+                if !self.frozen {
+                    self.whitespace = self.whitespace.saturating_sub(1);
+                    if self.whitespace == 0 && glyphs.spawns() {
+                        self.stream = Stream::On;
+                        self.color = stream_color(&state.color);
+                        self.whitespace =
+                            gen_len(&mut rng, 10, line_len) * self.sparsity * gap_scale(state);
+                    }
                 }
             }
             Stream::On => {
-                let line_len = self.line.len() - 1;
+                let line_len = self.line.len().saturating_sub(1);
                 let mut iter = self.line.iter_mut();
                 loop {
                     let next = iter.next();
@@ -109,25 +289,25 @@ impl LineState {
                         Some(cell) => match cell {
                             Cell::Whitespace => {
                                 if !updated {
-                                    let idx = thread_rng().gen_range(0..CHARSET.len());
-                                    let rand_char = CHARSET[idx] as char;
-                                    *cell = Cell::Sym(Sym {
-                                        value: rand_char.to_string(),
-                                        white: true,
-                                    });
+                                    if let Some(value) = next_glyph() {
+                                        *cell = Cell::Sym(Sym {
+                                            value,
+                                            white: true,
+                                            color,
+                                        });
+                                    }
                                     updated = true;
                                 }
                             }
                             Cell::Sym(sym) => match sym.white {
                                 true => {
-                                    let idx = thread_rng().gen_range(0..CHARSET.len());
-                                    let rand_char = CHARSET[idx] as char;
                                     sym.white = false;
                                     let next_cell = iter.next();
-                                    if let Some(cell) = next_cell {
+                                    if let (Some(cell), Some(value)) = (next_cell, next_glyph()) {
                                         *cell = Cell::Sym(Sym {
-                                            value: rand_char.to_string(),
+                                            value,
                                             white: true,
+                                            color: sym.color,
                                         });
                                     }
                                     updated = true;
@@ -148,22 +328,124 @@ impl LineState {
                 self.chars -= 1;
                 if self.chars == 0 {
                     self.stream = Stream::Off;
-                    self.chars = rng.gen_range(5..line_len);
+                    self.chars = gen_len(&mut rng, 5, line_len);
                 }
             }
         }
+        self.cursor = cursor;
+    }
+}
+
+// How far away a layer of rain is in the layered mode
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Depth {
+    Far,
+    Middle,
+    Near,
+}
+
+impl Depth {
+    // Dim the far layers, and only let the nearest one be bold
+    fn adjust_style(&self, style: Style) -> Style {
+        match self {
+            Depth::Far => style
+                .remove_modifier(ratatui::style::Modifier::BOLD)
+                .add_modifier(ratatui::style::Modifier::DIM),
+            Depth::Middle => style.remove_modifier(ratatui::style::Modifier::BOLD),
+            Depth::Near => style,
+        }
     }
 }
 
+// A plane of rain with its own lines. Further planes are dimmer, slower and
+// sparser, and nearer planes are drawn over them.
+pub struct Layer {
+    pub depth: Depth,
+    pub matrix: Vec<LineState>,
+    // The direction the layer falls, which only differs from the state while
+    // an old layer drains away after turning
+    pub direction: Direction,
+    // Left over from before a turn, and removed once it is empty
+    pub draining: bool,
+    // Only update the lines on every nth tick
+    pub every: u64,
+    // The sparsity given to each line of the layer as it's made, so further
+    // layers have fewer streams
+    pub sparsity: usize,
+}
+
+impl Layer {
+    // Create the layers from furthest to nearest. A single layer is the
+    // normal matrix.
+    pub fn create_layers(count: usize) -> Vec<Layer> {
+        let depths: &[Depth] = match count {
+            0 | 1 => &[Depth::Near],
+            2 => &[Depth::Far, Depth::Near],
+            _ => &[Depth::Far, Depth::Middle, Depth::Near],
+        };
+        depths
+            .iter()
+            .enumerate()
+            .map(|(i, depth)| {
+                // The furthest layer is the slowest and sparsest
+                let distance = depths.len() - i;
+                Layer {
+                    depth: *depth,
+                    matrix: Vec::new(),
+                    direction: Direction::Down,
+                    draining: false,
+                    every: distance as u64,
+                    sparsity: distance,
+                }
+            })
+            .collect()
+    }
+
+    // Stop the layer where it is and let what's on screen drain away
+    fn freeze(&mut self) {
+        self.draining = true;
+        for line in self.matrix.iter_mut() {
+            line.freeze();
+        }
+    }
+}
+
+// How many times longer than normal the gaps between streams are for the
+// whole matrix
+fn gap_scale(state: &State) -> usize {
+    match state.reduced_motion {
+        true => REDUCED_SPARSITY,
+        false => 1,
+    }
+}
+
+// The colors the matrix can change to from the one it is now
+pub fn other_colors(state: &State) -> Vec<&'static str> {
+    COLORS
+        .iter()
+        .copied()
+        .filter(|color| color != &state.color.as_str())
+        .filter(|color| !(state.reduced_motion && MOVING_COLORS.contains(color)))
+        .collect()
+}
+
+// Pick a random length of at least `min`, even when the terminal is too small
+// for the range to hold anything
+fn gen_len(rng: &mut impl Rng, min: usize, max: usize) -> usize {
+    rng.gen_range(min..max.max(min + 1))
+}
+
 // A symbol has a character value and either is white (first of stream) or not
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug)]
 pub struct Sym {
-    pub value: String,
+    pub value: Symbol,
     pub white: bool,
+    // Color of the stream the symbol belongs to
+    pub color: Option<Color>,
 }
 
 // A cell either is a symbol or a whitespace
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug)]
 pub enum Cell {
     Sym(Sym),
     Whitespace,
@@ -177,7 +459,7 @@ pub enum Stream {
 }
 //
 // The stream is either on (printing chars) or off (printing whitespace)
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Direction {
     Down,
     Up,
@@ -185,202 +467,595 @@ pub enum Direction {
     Right,
 }
 
-fn get_style(bold: bool, color: ratatui::style::Color) -> Style {
-    let mut style = Style::default();
-    style = style.fg(color);
-    if bold {
+impl Direction {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "down" => Some(Direction::Down),
+            "up" => Some(Direction::Up),
+            "left" => Some(Direction::Left),
+            "right" => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::Down => "down",
+            Direction::Up => "up",
+            Direction::Left => "left",
+            Direction::Right => "right",
+        }
+    }
+}
+
+// How long a tick takes at each speed from 1 to 10, in milliseconds
+pub fn speed_millis(speed: u8) -> u64 {
+    match speed {
+        1 => 120,
+        2 => 100,
+        3 => 80,
+        4 => 60,
+        5 => 50,
+        6 => 40,
+        7 => 30,
+        8 => 20,
+        9 => 10,
+        10 => 5,
+        _ => 60,
+    }
+}
+
+// Get a color from one of the color names or a hex code like `#001100`
+pub fn parse_color(color: &str) -> Option<Color> {
+    let color = color.to_lowercase();
+    color_from_name(&color).or_else(|| match color.strip_prefix('#') {
+        Some(_) => color.parse().ok(),
+        None => None,
+    })
+}
+
+// Get the terminal color for one of the color names
+pub fn color_from_name(color: &str) -> Option<Color> {
+    match color {
+        "blue" => Some(Color::Blue),
+        "cyan" => Some(Color::Cyan),
+        "red" => Some(Color::Red),
+        "purple" => Some(Color::Magenta),
+        "yellow" => Some(Color::Yellow),
+        "green" => Some(Color::Green),
+        "white" => Some(Color::White),
+        "black" => Some(Color::Black),
+        _ => None,
+    }
+}
+
+// Pick the color a new stream keeps for as long as it falls. Only the palette
+// colors have one, the rest are colored from the state when drawn.
+pub fn stream_color(color: &str) -> Option<Color> {
+    match color {
+        "rainbow" => Some(random_rainbow()),
+        _ => None,
+    }
+}
+
+// A color for a rainbow stream or a flickering symbol
+fn random_rainbow() -> Color {
+    RAINBOW[thread_rng().gen_range(0..RAINBOW.len())]
+}
+
+// The background for a cell, tinted towards the color of its symbol if enabled
+fn get_background(state: &State, color: Option<Color>) -> Style {
+    let Some(background) = state.background.filter(|_| !state.no_color) else {
+        return Style::default();
+    };
+    match (state.tint, color) {
+        // Heads are always white, and only the trail behind them is tinted
+        (true, Some(color)) if color != Color::White => {
+            Style::default().bg(state.color_depth.reduce(blend(background, color, TINT)))
+        }
+        _ => Style::default().bg(state.color_depth.reduce(background)),
+    }
+}
+
+// Paint the background over the whole frame, including the cells between
+// columns and past the last line that never get a symbol
+pub fn fill_background(frame: &mut Frame, area: Rect, state: &State) {
+    frame
+        .buffer_mut()
+        .set_style(area, get_background(state, None));
+}
+
+// Mix a small amount of one color into another
+fn blend(base: Color, tint: Color, amount: f32) -> Color {
+    let (r1, g1, b1) = to_rgb(base);
+    let (r2, g2, b2) = to_rgb(tint);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount).round() as u8;
+    Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+}
+
+// The RGB value of a color, using the xterm defaults for the named colors
+pub fn to_rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::Indexed(index) => indexed_rgb(index),
+        _ => (255, 255, 255),
+    }
+}
+
+fn get_style(state: &State, color: ratatui::style::Color) -> Style {
+    if state.no_color {
+        // Only the heads stand out, by being bold
+        return match state.bold || color == Color::White {
+            true => Style::default().add_modifier(ratatui::style::Modifier::BOLD),
+            false => Style::default(),
+        };
+    }
+    let mut style = get_background(state, Some(color));
+    style = style.fg(state.color_depth.reduce(color));
+    if state.bold || state.color == "high-contrast" {
         style = style.add_modifier(ratatui::style::Modifier::BOLD);
     }
     style
 }
 
-fn get_color_col(color: &str, bold: bool, sym: Sym) -> Line {
-    match sym.white {
-        true => Line::from(Span::styled(
-            sym.value,
-            get_style(bold, ratatui::style::Color::White),
-        )),
-        false => match color {
-            "blue" => Line::from(Span::styled(
-                sym.value,
-                get_style(bold, ratatui::style::Color::Blue),
-            )),
-            "cyan" => Line::from(Span::styled(
-                sym.value,
-                get_style(bold, ratatui::style::Color::Cyan),
-            )),
-            "red" => Line::from(Span::styled(
-                sym.value,
-                get_style(bold, ratatui::style::Color::Red),
-            )),
-            "purple" => Line::from(Span::styled(
-                sym.value,
-                get_style(bold, ratatui::style::Color::Magenta),
-            )),
-            "yellow" => Line::from(Span::styled(
-                sym.value,
-                get_style(bold, ratatui::style::Color::Yellow),
-            )),
-            "rainbow" => {
-                let mut rng = thread_rng();
-                let colors = ["blue", "cyan", "red", "purple", "yellow", "green"];
-                let index = rng.gen_range(0..=colors.len() - 1);
-                let color = colors[index];
-                match color {
-                    "blue" => Line::from(Span::styled(
-                        sym.value,
-                        get_style(bold, ratatui::style::Color::Blue),
-                    )),
-                    "cyan" => Line::from(Span::styled(
-                        sym.value,
-                        get_style(bold, ratatui::style::Color::Cyan),
-                    )),
-                    "red" => Line::from(Span::styled(
-                        sym.value,
-                        get_style(bold, ratatui::style::Color::Red),
-                    )),
-                    "purple" => Line::from(Span::styled(
-                        sym.value,
-                        get_style(bold, ratatui::style::Color::Magenta),
-                    )),
-                    "yellow" => Line::from(Span::styled(
-                        sym.value,
-                        get_style(bold, ratatui::style::Color::Yellow),
-                    )),
-                    _ => Line::from(Span::styled(
-                        sym.value,
-                        get_style(bold, ratatui::style::Color::Green),
-                    )),
-                }
-            }
-            _ => Line::from(Span::styled(
-                sym.value,
-                get_style(bold, ratatui::style::Color::Green),
-            )),
-        },
+// Colors that move with time and across the screen, in truecolor
+fn hue_color(state: &State, x: usize, y: usize, area: Rect) -> Option<Color> {
+    if let Some(hue) = state.hue {
+        return Some(hsv_to_color(hue.rem_euclid(360.0), state.saturation));
     }
+    let time = state.start.elapsed().as_secs_f32() / state.period;
+    let width = area.width.max(1) as f32;
+    // Cells are about twice as tall as they are wide
+    let diagonal = (area.width + area.height * 2).max(1) as f32;
+    let turns = match state.color.as_str() {
+        // The whole screen changes hue together
+        "cycle" => time,
+        // A rainbow across the screen that slowly slides sideways
+        "spectrum" => x as f32 / width + time,
+        // Bands of color moving from the top left to the bottom right
+        "wave" => (x as f32 + y as f32 * 2.0) / diagonal - time,
+        _ => return None,
+    };
+    Some(hsv_to_color(
+        turns.rem_euclid(1.0) * 360.0,
+        state.saturation,
+    ))
 }
 
-pub fn process_matrix_cols(
+// Convert a hue in degrees and a saturation at full brightness to RGB
+fn hsv_to_color(hue: f32, saturation: f32) -> Color {
+    let chroma = saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |c: f32| ((c + 1.0 - chroma) * 255.0).round() as u8;
+    Color::Rgb(channel(r), channel(g), channel(b))
+}
+
+// What to draw in one cell on screen, borrowed straight from the line
+struct Glyph {
+    value: Symbol,
+    white: bool,
+    color: Option<Color>,
+}
+
+impl Glyph {
+    fn from_cell(cell: &Cell) -> Option<Self> {
+        match cell {
+            Cell::Sym(sym) => Some(Glyph {
+                value: sym.value,
+                white: sym.white,
+                color: sym.color,
+            }),
+            Cell::Whitespace => None,
+        }
+    }
+}
+
+// Find the glyph at a position on screen. Up and left read the line from the
+// end instead of reversing it.
+fn get_glyph(line: &[Cell], pos: usize, direction: &Direction) -> Option<Glyph> {
+    let reversed = *direction == Direction::Up || *direction == Direction::Left;
+    let index = match reversed {
+        true => line.len().checked_sub(pos + 1)?,
+        false => pos,
+    };
+    line.get(index).and_then(Glyph::from_cell)
+}
+
+// Find the glyph at a position on screen when the line is simulated at twice
+// the resolution, so each cell covers a pair. A head that has only moved
+// halfway into a cell is drawn as a half block so it glides instead of
+// jumping a whole cell at a time.
+fn get_smooth_glyph(line: &[Cell], pos: usize, direction: &Direction) -> Option<Glyph> {
+    // The half of each pair a head reaches first, and the block that fills it
+    let (entering, edge) = match direction {
+        Direction::Down => (0, '▀'),
+        Direction::Up => (1, '▄'),
+        Direction::Right => (0, '▌'),
+        Direction::Left => (1, '▐'),
+    };
+    let first = get_glyph(line, pos * 2 + entering, direction);
+    let second = get_glyph(line, pos * 2 + 1 - entering, direction);
+    match (first, second) {
+        (Some(head), None) if head.white => Some(Glyph {
+            value: Symbol::Char(edge),
+            white: true,
+            color: head.color,
+        }),
+        // Prefer the half the head left last so the symbol doesn't change
+        (first, Some(mut glyph)) => {
+            glyph.white |= first.is_some_and(|head| head.white);
+            Some(glyph)
+        }
+        (first, None) => first,
+    }
+}
+
+fn get_glyph_style(state: &State, glyph: &Glyph, x: usize, y: usize, area: Rect) -> Style {
+    // Palettes have their own color for the heads too
+    if let Some(palette) = palette(&state.color).filter(|_| !state.no_color) {
+        let color = match glyph.white {
+            true => palette.head,
+            false => glyph
+                .color
+                .or_else(|| hue_color(state, x, y, area))
+                .unwrap_or(palette.trail),
+        };
+        return get_style(state, color);
+    }
+    if glyph.white {
+        return get_style(state, Color::White);
+    }
+    // Streams with their own color ignore the color from the state
+    if let Some(fixed) = glyph.color.or_else(|| hue_color(state, x, y, area)) {
+        return get_style(state, fixed);
+    }
+    let color = match state.color.as_str() {
+        "flicker" => Some(random_rainbow()),
+        // The brightest green, which stands out the most on black
+        "high-contrast" => Some(Color::LightGreen),
+        color => color_from_name(color),
+    };
+    get_style(state, color.unwrap_or(Color::Green))
+}
+
+// Write one cell of a line straight into the buffer
+fn draw_cell(
+    buf: &mut Buffer,
+    position: Position,
+    glyph: Option<Glyph>,
+    style: Style,
+    state: &State,
+    over: bool,
+) {
+    match glyph {
+        Some(glyph) => {
+            let mut text = [0; 4];
+            buf[position]
+                .set_symbol(glyph.value.as_str(&mut text))
+                .set_style(style);
+        }
+        // Leave the gaps alone so the layers behind show through
+        None if over => {}
+        None => {
+            buf[position]
+                .set_symbol(" ")
+                .set_style(get_background(state, None));
+        }
+    }
+}
+
+// Draw the line at `i` from the edge of the area, either a column or a row
+fn draw_line(
     i: usize,
-    line: Rect,
+    area: Rect,
     frame: &mut Frame,
-    matrix: &mut [LineState],
+    layer: &Layer,
     state: &State,
+    over: bool,
+    vertical: bool,
 ) {
-    if i / 2 >= matrix.len() {
+    let buf = frame.buffer_mut();
+    let (line, line_state) = match vertical {
+        true => (
+            Rect::new(area.x + i as u16, area.y, 1, area.height),
+            layer.matrix.get(i / 2),
+        ),
+        false => (
+            Rect::new(area.x, area.y + i as u16, area.width, 1),
+            layer.matrix.get(i),
+        ),
+    };
+    let Some(line_state) = line_state else {
         return;
-    }
-    let line_state = matrix.get_mut(i / 2).unwrap();
-    if state.direction == Direction::Up || state.direction == Direction::Left {
-        line_state.line.reverse();
-    }
-    let new_line = line_state.line.clone();
-    let lines: Vec<Line> = new_line
-        .into_iter()
-        .map(|cell| {
-            // Determine how to print each line
-            match cell {
-                Cell::Sym(sym) => get_color_col(state.color.as_str(), state.bold, sym),
-                Cell::Whitespace => Line::from(String::from(" ")),
-            }
-        })
-        .collect();
-    // Render the line as a paragraph
-    frame.render_widget(Paragraph::new(Text::from(lines)), line);
-    if state.direction == Direction::Up || state.direction == Direction::Left {
-        line_state.line.reverse();
-    }
-}
-
-fn get_color_row(color: &str, bold: bool, sym: Sym) -> Span {
-    match sym.white {
-        true => Span::styled(sym.value, Style::default().fg(ratatui::style::Color::White)),
-        false => match color {
-            "blue" => Span::styled(sym.value, get_style(bold, ratatui::style::Color::Blue)),
-            "cyan" => Span::styled(sym.value, get_style(bold, ratatui::style::Color::Cyan)),
-            "red" => Span::styled(sym.value, get_style(bold, ratatui::style::Color::Red)),
-            "purple" => Span::styled(sym.value, get_style(bold, ratatui::style::Color::Magenta)),
-            "yellow" => Span::styled(sym.value, get_style(bold, ratatui::style::Color::Yellow)),
-            "rainbow" => {
-                let mut rng = thread_rng();
-                let colors = ["blue", "cyan", "red", "purple", "yellow", "green"];
-                let index = rng.gen_range(0..=colors.len() - 1);
-                let color = colors[index];
-                match color {
-                    "blue" => Span::styled(sym.value, get_style(bold, ratatui::style::Color::Blue)),
-                    "cyan" => Span::styled(sym.value, get_style(bold, ratatui::style::Color::Cyan)),
-                    "red" => Span::styled(sym.value, get_style(bold, ratatui::style::Color::Red)),
-                    "purple" => {
-                        Span::styled(sym.value, get_style(bold, ratatui::style::Color::Magenta))
-                    }
-                    "yellow" => {
-                        Span::styled(sym.value, get_style(bold, ratatui::style::Color::Yellow))
-                    }
-                    _ => Span::styled(sym.value, get_style(bold, ratatui::style::Color::Green)),
-                }
+    };
+    for (pos, position) in line.positions().enumerate() {
+        let glyph = match state.smooth {
+            true => get_smooth_glyph(&line_state.line, pos, &layer.direction),
+            false => get_glyph(&line_state.line, pos, &layer.direction),
+        };
+        let (x, y) = if vertical { (i, pos) } else { (pos, i) };
+        let style = match &glyph {
+            // Far layers are as bright as the rest in high contrast
+            Some(glyph) if state.color == "high-contrast" => {
+                get_glyph_style(state, glyph, x, y, area)
             }
-            _ => Span::styled(sym.value, get_style(bold, ratatui::style::Color::Green)),
-        },
+            Some(glyph) => layer
+                .depth
+                .adjust_style(get_glyph_style(state, glyph, x, y, area)),
+            None => Style::default(),
+        };
+        draw_cell(buf, position, glyph, style, state, over);
     }
 }
 
+pub fn process_matrix_cols(
+    i: usize,
+    area: Rect,
+    frame: &mut Frame,
+    layer: &Layer,
+    state: &State,
+    over: bool,
+) {
+    draw_line(i, area, frame, layer, state, over, true);
+}
+
 pub fn process_matrix_rows(
     i: usize,
-    line: Rect,
+    area: Rect,
     frame: &mut Frame,
-    matrix: &mut [LineState],
+    layer: &Layer,
     state: &State,
+    over: bool,
 ) {
-    if i >= matrix.len() {
-        return;
+    draw_line(i, area, frame, layer, state, over, false);
+}
+
+// Update the lines of every layer that moves on this tick
+pub fn update_layers(layers: &mut Vec<Layer>, state: &State, tick: u64) {
+    for layer in layers.iter_mut() {
+        if tick % layer.every == 0 {
+            for line in layer.matrix.iter_mut() {
+                line.update_line(state);
+            }
+        }
     }
-    let line_state = matrix.get_mut(i).unwrap();
-    if state.direction == Direction::Up || state.direction == Direction::Left {
-        line_state.line.reverse();
-    }
-    let new_line = line_state.line.clone();
-    let lines: Vec<Span> = new_line
-        .into_iter()
-        .map(|cell| {
-            // Determine how to print each line
-            match cell {
-                Cell::Sym(sym) => get_color_row(state.color.as_str(), state.bold, sym),
-                Cell::Whitespace => Span::from(String::from(" ")),
+    // Drop the layers from before a turn once they have drained away
+    layers.retain(|layer| !layer.draining || layer.matrix.iter().any(|line| !line.is_idle()));
+}
+
+// Draw the layers into an area of the frame, which may be all of it
+pub fn draw_layers(frame: &mut Frame, area: Rect, layers: &[Layer], state: &State) {
+    let area = area.intersection(frame.area());
+    fill_background(frame, area, state);
+    // Draw the layers from furthest to nearest so near symbols cover far ones
+    for (depth, layer) in layers.iter().enumerate() {
+        let over = depth > 0;
+        if layer.direction == Direction::Up || layer.direction == Direction::Down {
+            // Only print matrix every other column
+            // Looks better than using every column
+            for i in (0..usize::from(area.width)).step_by(2) {
+                process_matrix_cols(i, area, frame, layer, state, over);
+            }
+        } else {
+            // Get the state of every row
+            for i in 0..usize::from(area.height) {
+                process_matrix_rows(i, area, frame, layer, state, over);
             }
+        }
+    }
+}
+
+// How many lines there are and how long each one is for an area
+fn matrix_size(size: Rect, state: &State) -> (usize, usize) {
+    // Smooth motion simulates two cells for every one on screen
+    let scale = if state.smooth { 2 } else { 1 };
+    if state.direction == Direction::Up || state.direction == Direction::Down {
+        // Only need half the columns because using all looks cluttered
+        (
+            usize::from(size.width / 2 + 1),
+            usize::from(size.height) * scale,
+        )
+    } else {
+        (
+            usize::from(size.height.saturating_sub(1)),
+            usize::from(size.width) * scale,
+        )
+    }
+}
+
+pub fn create_matrix(layers: &mut [Layer], area: Rect, state: &State) {
+    let (count, len) = matrix_size(area, state);
+    fill_layers(layers, count, len, state);
+}
+
+fn fill_layers(layers: &mut [Layer], count: usize, len: usize, state: &State) {
+    for layer in layers.iter_mut() {
+        // Create new matrix where each column has its own state
+        layer.matrix = (0..count)
+            .map(|_| LineState::new(len, state, layer.sparsity))
+            .collect();
+        layer.direction = state.direction;
+
+        info!("Matrix len: {}", layer.matrix.len());
+    }
+}
+
+// Start falling in the new direction from the state. What's already on screen
+// stays where it is and drains away under the new streams.
+pub fn turn_matrix(layers: &mut Vec<Layer>, area: Rect, state: &State) {
+    let (count, len) = matrix_size(area, state);
+    let mut turned: Vec<Layer> = layers
+        .iter()
+        .filter(|layer| !layer.draining)
+        .map(|layer| Layer {
+            depth: layer.depth,
+            matrix: Vec::new(),
+            direction: state.direction,
+            draining: false,
+            every: layer.every,
+            sparsity: layer.sparsity,
         })
         .collect();
-    // Render the line
-    frame.render_widget(Line::from(lines), line);
-    if state.direction == Direction::Up || state.direction == Direction::Left {
-        line_state.line.reverse();
+    fill_layers(&mut turned, count, len, state);
+
+    for layer in layers.iter_mut() {
+        layer.freeze();
     }
+    // The old layers are drawn first so the new streams fall over them
+    layers.append(&mut turned);
 }
 
-pub fn create_matrix(
-    matrix: &mut Vec<LineState>,
-    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-    state: &State,
-) -> Result<()> {
-    let terminal_size = terminal.size().unwrap();
-    let t_height = terminal_size.height;
-    let t_width = terminal_size.width;
-    terminal.clear()?;
-
-    // Create new matrix where each column has its own state
-    // Only need half the columns because using all looks cluttered
-    *matrix = Vec::new();
-    if state.direction == Direction::Up || state.direction == Direction::Down {
-        for _ in 0..t_width / 2 + 1 {
-            matrix.push(LineState::new(t_height.into()));
+// Fit the matrix to a new size without losing what is on screen. Lines are
+// added or removed at the edge and the rest are cut or extended.
+pub fn resize_matrix(layers: &mut Vec<Layer>, area: Rect, state: &State) {
+    let (count, len) = matrix_size(area, state);
+    // Whatever is still draining from a turn won't fit the new size
+    layers.retain(|layer| !layer.draining);
+
+    for layer in layers.iter_mut() {
+        layer.matrix.truncate(count);
+        for line in layer.matrix.iter_mut() {
+            line.resize(len, &state.direction);
         }
-    } else {
-        for _ in 0..t_height - 1 {
-            matrix.push(LineState::new(t_width.into()));
+        while layer.matrix.len() < count {
+            layer
+                .matrix
+                .push(LineState::new(len, state, layer.sparsity));
         }
+
+        info!("Matrix len: {}", layer.matrix.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glyphs::launch_glyphs;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    // Draw the layers and read back the first column
+    fn column(layers: &[Layer], state: &State, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(1, height)).unwrap();
+        terminal
+            .draw(|frame| draw_layers(frame, frame.area(), layers, state))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height).map(|y| buffer[(0, y)].symbol()).collect()
+    }
+
+    #[test]
+    fn smooth_source_shows_every_glyph() {
+        let state = State {
+            glyphs: Glyphs::from_text("ABCDEFGH", true).unwrap(),
+            smooth: true,
+            ..State::default()
+        };
+        let mut layers = Layer::create_layers(1);
+        let mut line = LineState::new(16, &state, 1);
+        line.stream = Stream::On;
+        line.cursor = 0;
+        line.chars = 16;
+        for _ in 0..16 {
+            line.update_line(&state);
+        }
+        layers[0].matrix = vec![line];
+        assert_eq!(column(&layers, &state, 8), "ABCDEFGH");
     }
 
-    info!("Matrix len: {}", matrix.len());
+    const DIRECTIONS: [Direction; 4] = [
+        Direction::Down,
+        Direction::Up,
+        Direction::Left,
+        Direction::Right,
+    ];
 
-    Ok(())
+    #[test]
+    fn resize_line_while_streaming() {
+        let state = State {
+            glyphs: Glyphs::from_text("resize", false).unwrap(),
+            ..State::default()
+        };
+        let Glyphs::Source(source) = &state.glyphs else {
+            unreachable!();
+        };
+        let launched = launch_glyphs("launched text", false);
+        for direction in DIRECTIONS {
+            let mut line = LineState::new(20, &state, 1);
+            line.launch(launched.clone(), None);
+            for len in [20, 10, 0, 0, 30, 5, 40] {
+                line.resize(len, &direction);
+                assert_eq!(line.line.len(), len);
+                for _ in 0..25 {
+                    line.update_line(&state);
+                    assert_eq!(line.line.len(), len);
+                    assert!(line.cursor < source.len());
+                    assert!(line.queue.len() <= launched.len());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn frozen_line_starts_nothing() {
+        let state = State::default();
+        let mut line = LineState::new(20, &state, 1);
+        line.freeze();
+        line.set_sparsity(3);
+        for _ in 0..200 {
+            line.update_line(&state);
+            assert!(matches!(line.stream, Stream::Off));
+            assert!(line.is_idle());
+        }
+    }
+
+    #[test]
+    fn resize_matrix_while_streaming() {
+        let mut terminal = Terminal::new(TestBackend::new(40, 20)).unwrap();
+        for direction in DIRECTIONS {
+            for smooth in [false, true] {
+                let state = State {
+                    direction,
+                    smooth,
+                    ..State::default()
+                };
+                let mut layers = Layer::create_layers(3);
+                create_matrix(&mut layers, Rect::new(0, 0, 20, 10), &state);
+                let mut tick = 0;
+                for (width, height) in [(20, 10), (5, 3), (0, 0), (1, 1), (40, 20), (20, 10)] {
+                    let area = Rect::new(0, 0, width, height);
+                    resize_matrix(&mut layers, area, &state);
+                    for _ in 0..30 {
+                        update_layers(&mut layers, &state, tick);
+                        tick += 1;
+                    }
+                    terminal
+                        .draw(|frame| draw_layers(frame, area, &layers, &state))
+                        .unwrap();
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,70 @@
+use crate::pane::{Pane, PaneLayout};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use std::io::Result;
+
+// A color for the heads and one for the trails behind them
+pub struct Palette {
+    pub name: &'static str,
+    pub head: Color,
+    pub trail: Color,
+}
+
+// Palettes for color blindness keep to the hues each kind can still tell
+// apart, and make the heads stand out by being much lighter than the trails
+// rather than by their hue
+pub const PALETTES: &[Palette] = &[
+    // Red and green look alike, so blue trails under pale yellow heads
+    Palette {
+        name: "deuteranopia",
+        head: Color::Rgb(255, 244, 180),
+        trail: Color::Rgb(0, 114, 178),
+    },
+    // Reds look dark as well, so amber trails under near white heads
+    Palette {
+        name: "protanopia",
+        head: Color::Rgb(250, 250, 235),
+        trail: Color::Rgb(170, 120, 0),
+    },
+    // Blue and yellow look alike, so red trails under pale pink heads
+    Palette {
+        name: "tritanopia",
+        head: Color::Rgb(255, 230, 235),
+        trail: Color::Rgb(204, 51, 85),
+    },
+];
+
+pub fn palette(name: &str) -> Option<&'static Palette> {
+    PALETTES.iter().find(|palette| palette.name == name)
+}
+
+// The colors shown side by side by `preview`, starting with the usual green
+// to compare against
+pub const PREVIEW: &[&str] = &[
+    "green",
+    "high-contrast",
+    "deuteranopia",
+    "protanopia",
+    "tritanopia",
+];
+
+// A pane for each of the colors in the preview
+pub fn preview_layout() -> Result<PaneLayout> {
+    PaneLayout::parse(&format!("h:{}", vec!["1"; PREVIEW.len()].join(",")))
+}
+
+// Label each pane of the preview with its color
+pub fn draw_labels(frame: &mut Frame, panes: &[Pane]) {
+    for pane in panes {
+        let name = pane.state.color.as_str();
+        let width = (name.len() as u16 + 2).min(pane.area.width);
+        let area = Rect::new(pane.area.x, pane.area.y, width, 1).intersection(frame.area());
+        frame.render_widget(
+            Paragraph::new(format!(" {name} "))
+                .style(Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)),
+            area,
+        );
+    }
+}
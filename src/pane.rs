@@ -0,0 +1,210 @@
+use crate::matrix::{
+    create_matrix, draw_layers, resize_matrix, turn_matrix, update_layers, Direction, Layer, State,
+    REDUCED_SPEED,
+};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::Frame;
+use std::io::{Error, ErrorKind, Result};
+use std::time::{Duration, Instant};
+
+// How the screen is split into panes
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaneLayout {
+    // Whether the sections are side by side, or stacked on top of each other
+    horizontal: bool,
+    // How many panes each section is split into, across the other way
+    sections: Vec<u16>,
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        Self {
+            horizontal: true,
+            sections: vec![1],
+        }
+    }
+}
+
+impl PaneLayout {
+    // Either a grid like `2x2` for columns by rows, or sections like `h:1,2`
+    // for a column with one pane next to a column split in two. `v:` stacks
+    // the sections as rows instead.
+    pub fn parse(layout: &str) -> Result<Self> {
+        let invalid = || {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("layout `{layout}` should be like 2x2, h:1,2 or v:2,1"),
+            )
+        };
+        let count = |n: &str| match n.trim().parse::<u16>() {
+            Ok(n @ 1..=8) => Ok(n),
+            _ => Err(invalid()),
+        };
+
+        let layout = layout.to_lowercase();
+        let (horizontal, sections) = match layout.split_once(':') {
+            Some(("h", sections)) => (true, sections),
+            Some(("v", sections)) => (false, sections),
+            Some(_) => return Err(invalid()),
+            None => {
+                let (columns, rows) = layout.split_once('x').ok_or_else(invalid)?;
+                let sections = vec![count(rows)?; usize::from(count(columns)?)];
+                return Ok(Self {
+                    horizontal: true,
+                    sections,
+                });
+            }
+        };
+        let sections = sections
+            .split(',')
+            .map(count)
+            .collect::<Result<Vec<u16>>>()?;
+        if sections.len() > 8 {
+            return Err(invalid());
+        }
+        Ok(Self {
+            horizontal,
+            sections,
+        })
+    }
+
+    // The area of every pane, going through each section in turn
+    pub fn areas(&self, area: Rect) -> Vec<Rect> {
+        let split = |area: Rect, n: u16, horizontal: bool| {
+            let constraints = (0..n).map(|_| Constraint::Ratio(1, u32::from(n)));
+            match horizontal {
+                true => Layout::horizontal(constraints).split(area),
+                false => Layout::vertical(constraints).split(area),
+            }
+        };
+        let sections = split(area, self.sections.len() as u16, self.horizontal);
+        sections
+            .iter()
+            .zip(self.sections.iter())
+            .flat_map(|(section, n)| split(*section, *n, !self.horizontal).to_vec())
+            .collect()
+    }
+}
+
+// One part of the screen with its own rain
+pub struct Pane {
+    pub state: State,
+    pub layers: Vec<Layer>,
+    pub area: Rect,
+    tick: u64,
+    // When the rain in this pane should next move
+    next: Instant,
+}
+
+impl Pane {
+    pub fn new(state: State, layers: usize, area: Rect) -> Self {
+        let mut layers = Layer::create_layers(layers);
+        create_matrix(&mut layers, area, &state);
+        Self {
+            state,
+            layers,
+            area,
+            tick: 0,
+            next: Instant::now(),
+        }
+    }
+
+    // How long a tick takes, where smooth motion takes two to move a cell
+    pub fn tick_time(&self) -> Duration {
+        let speed = match self.state.reduced_motion {
+            true => self.state.speed.max(REDUCED_SPEED),
+            false => self.state.speed,
+        };
+        match self.state.smooth {
+            true => Duration::from_millis(speed / 2),
+            false => Duration::from_millis(speed),
+        }
+    }
+
+    pub fn next(&self) -> Instant {
+        self.next
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        now >= self.next
+    }
+
+    pub fn update(&mut self, now: Instant) {
+        update_layers(&mut self.layers, &self.state, self.tick);
+        self.tick += 1;
+        self.next = now + self.tick_time();
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        draw_layers(frame, self.area, &self.layers, &self.state);
+    }
+
+    pub fn resize(&mut self, area: Rect) {
+        self.area = area;
+        resize_matrix(&mut self.layers, area, &self.state);
+    }
+
+    pub fn turn(&mut self, direction: Direction) {
+        self.state.direction = direction;
+        turn_matrix(&mut self.layers, self.area, &self.state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(horizontal: bool, sections: &[u16]) -> PaneLayout {
+        PaneLayout {
+            horizontal,
+            sections: sections.to_vec(),
+        }
+    }
+
+    #[test]
+    fn grids() {
+        assert_eq!(PaneLayout::parse("2x2").unwrap(), layout(true, &[2, 2]));
+        assert_eq!(PaneLayout::parse("3X1").unwrap(), layout(true, &[1, 1, 1]));
+    }
+
+    #[test]
+    fn sections() {
+        assert_eq!(PaneLayout::parse("h:1,2").unwrap(), layout(true, &[1, 2]));
+        assert_eq!(PaneLayout::parse("V:2, 1").unwrap(), layout(false, &[2, 1]));
+        assert_eq!(PaneLayout::parse("v:3").unwrap(), layout(false, &[3]));
+    }
+
+    #[test]
+    fn invalid_layouts() {
+        for invalid in [
+            "",
+            "0x2",
+            "2x",
+            "9x1",
+            "2x2x2",
+            "h:",
+            "h:1,,2",
+            "h:0",
+            "x:1",
+            "h:1,1,1,1,1,1,1,1,1",
+        ] {
+            let error = PaneLayout::parse(invalid).err();
+            assert!(error.is_some(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn areas() {
+        let areas = PaneLayout::parse("h:1,2")
+            .unwrap()
+            .areas(Rect::new(0, 0, 10, 10));
+        assert_eq!(
+            areas,
+            [
+                Rect::new(0, 0, 5, 10),
+                Rect::new(5, 0, 5, 5),
+                Rect::new(5, 5, 5, 5)
+            ]
+        );
+    }
+}
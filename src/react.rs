@@ -0,0 +1,276 @@
+use crate::matrix::{speed_millis, Direction};
+use crate::pane::{Pane, PaneLayout};
+use log::debug;
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::time::{Duration, Instant};
+
+const STAT: &str = "/proc/stat";
+const NET_DEV: &str = "/proc/net/dev";
+// How often the load is read
+const SAMPLE: Duration = Duration::from_secs(1);
+// How many times sparser than normal the rain is when nothing is happening
+const MAX_SPARSITY: f32 = 6.0;
+// The least traffic that counts as busy, in bytes a second, so a quiet link
+// doesn't pour down just because it's the busiest it has been
+const MIN_PEAK: f32 = 64.0 * 1024.0;
+// How much of the busiest traffic is remembered after each sample, so the
+// scale comes back down after a burst
+const PEAK_DECAY: f32 = 0.99;
+
+// What the rain follows
+#[derive(Clone, Debug, PartialEq)]
+pub enum React {
+    // All the rain follows the total CPU usage
+    Cpu,
+    // The lines are split into a group for each core, and each group follows
+    // its core. The speed still follows the total.
+    CpuPerCore,
+    // Received traffic falls down every other pane and sent traffic rises up
+    // the rest, for one interface or all but loopback
+    Net(Option<String>),
+}
+
+impl React {
+    pub fn parse(react: &str) -> Result<Self> {
+        match react.split_once(':') {
+            Some(("net", iface)) if !iface.is_empty() => Ok(Self::Net(Some(iface.to_string()))),
+            _ => match react.to_lowercase().as_str() {
+                "cpu" => Ok(Self::Cpu),
+                "cpu-per-core" => Ok(Self::CpuPerCore),
+                "net" => Ok(Self::Net(None)),
+                _ => Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("react `{react}` should be cpu, cpu-per-core, net or net:IFACE"),
+                )),
+            },
+        }
+    }
+
+    // The layout to use when none is given
+    pub fn layout(&self) -> PaneLayout {
+        match self {
+            // Side by side halves for received and sent traffic
+            React::Net(_) => PaneLayout::parse("h:1,1").unwrap_or_default(),
+            _ => PaneLayout::default(),
+        }
+    }
+
+    // Which way the rain in a pane should go, if it follows something that
+    // has a direction
+    pub fn direction(&self, pane: usize) -> Option<Direction> {
+        match self {
+            React::Net(_) if pane % 2 == 0 => Some(Direction::Down),
+            React::Net(_) => Some(Direction::Up),
+            _ => None,
+        }
+    }
+}
+
+// Time a CPU has spent idle and in total since boot, in clock ticks
+#[derive(Clone, Copy, Default)]
+struct Times {
+    idle: u64,
+    total: u64,
+}
+
+// Read the total first and then each core
+fn read_times() -> Result<Vec<Times>> {
+    let stat = read(STAT)?;
+    let times = stat
+        .lines()
+        .filter(|line| line.starts_with("cpu"))
+        .map(|line| {
+            // user nice system idle iowait irq softirq steal, where guest time
+            // is already counted in user
+            let fields = line
+                .split_whitespace()
+                .skip(1)
+                .take(8)
+                .map(|field| field.parse::<u64>().unwrap_or(0))
+                .collect::<Vec<u64>>();
+            Times {
+                idle: fields.iter().skip(3).take(2).sum(),
+                total: fields.iter().sum(),
+            }
+        })
+        .collect::<Vec<Times>>();
+    if times.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("no CPU times in {STAT}"),
+        ));
+    }
+    Ok(times)
+}
+
+// Bytes received and sent since boot on an interface, or on every interface
+// but loopback
+fn read_bytes(iface: Option<&str>) -> Result<(u64, u64)> {
+    let dev = read(NET_DEV)?;
+    let mut found = false;
+    let mut bytes = (0, 0);
+    // The first two lines are headers
+    for line in dev.lines().skip(2) {
+        let Some((name, counters)) = line.split_once(':') else {
+            continue;
+        };
+        let name = name.trim();
+        let wanted = match iface {
+            Some(iface) => name == iface,
+            None => name != "lo",
+        };
+        if !wanted {
+            continue;
+        }
+        found = true;
+        // Received bytes come first, and sent bytes are after the other
+        // seven received counters
+        let counters = counters
+            .split_whitespace()
+            .map(|counter| counter.parse::<u64>().unwrap_or(0))
+            .collect::<Vec<u64>>();
+        bytes.0 += counters.first().copied().unwrap_or(0);
+        bytes.1 += counters.get(8).copied().unwrap_or(0);
+    }
+    match (found, iface) {
+        (false, Some(iface)) => Err(Error::new(
+            ErrorKind::NotFound,
+            format!("no interface `{iface}` in {NET_DEV}"),
+        )),
+        _ => Ok(bytes),
+    }
+}
+
+fn read(path: &str) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| Error::new(e.kind(), format!("can't read {path}: {e}")))
+}
+
+// The counters from the last sample
+enum Counters {
+    Cpu(Vec<Times>),
+    Net {
+        bytes: (u64, u64),
+        // The busiest traffic lately, received then sent
+        peak: (f32, f32),
+    },
+}
+
+// Reads the load every so often and turns it into how fast and dense the rain
+// is, using the same speed and sparsity the keys and layers set
+pub struct Reactor {
+    react: React,
+    counters: Counters,
+    // How busy each thing followed was over the last sample, from 0.0 to 1.0.
+    // The total CPU and then each core, or received and then sent traffic.
+    levels: Vec<f32>,
+    sampled: Instant,
+    next: Instant,
+}
+
+impl Reactor {
+    pub fn new(react: React) -> Result<Self> {
+        let counters = match &react {
+            React::Net(iface) => Counters::Net {
+                bytes: read_bytes(iface.as_deref())?,
+                peak: (MIN_PEAK, MIN_PEAK),
+            },
+            _ => Counters::Cpu(read_times()?),
+        };
+        let now = Instant::now();
+        Ok(Self {
+            react,
+            counters,
+            levels: Vec::new(),
+            sampled: now,
+            next: now + SAMPLE,
+        })
+    }
+
+    pub fn next(&self) -> Instant {
+        self.next
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        now >= self.next
+    }
+
+    // Read the load and work out the new levels. On an error the last levels
+    // are kept, and the next try waits for the next sample.
+    pub fn sample(&mut self, now: Instant) -> Result<()> {
+        self.next = now + SAMPLE;
+        let seconds = now.duration_since(self.sampled).as_secs_f32().max(0.001);
+        match (&self.react, &mut self.counters) {
+            (React::Net(iface), Counters::Net { bytes, peak }) => {
+                let current = read_bytes(iface.as_deref())?;
+                let rx = current.0.saturating_sub(bytes.0) as f32 / seconds;
+                let tx = current.1.saturating_sub(bytes.1) as f32 / seconds;
+                *peak = (
+                    (peak.0 * PEAK_DECAY).max(rx).max(MIN_PEAK),
+                    (peak.1 * PEAK_DECAY).max(tx).max(MIN_PEAK),
+                );
+                *bytes = current;
+                self.levels = vec![rx / peak.0, tx / peak.1];
+                debug!("Network: {rx:.0} B/s received, {tx:.0} B/s sent");
+            }
+            (_, Counters::Cpu(times)) => {
+                let current = read_times()?;
+                self.levels = current
+                    .iter()
+                    .zip(times.iter())
+                    .map(|(now, then)| {
+                        let total = now.total.saturating_sub(then.total);
+                        let idle = now.idle.saturating_sub(then.idle);
+                        match total {
+                            0 => 0.0,
+                            _ => 1.0 - idle as f32 / total as f32,
+                        }
+                    })
+                    .collect();
+                *times = current;
+                debug!("CPU usage: {:?}", self.levels);
+            }
+            _ => {}
+        }
+        self.sampled = now;
+        Ok(())
+    }
+
+    pub fn apply(&self, panes: &mut [Pane]) {
+        let Some((first, rest)) = self.levels.split_first() else {
+            return;
+        };
+        for (i, pane) in panes.iter_mut().enumerate() {
+            let level = match self.react {
+                React::Net(_) => self.levels[i % self.levels.len()],
+                _ => *first,
+            };
+            pane.state.speed = speed(level);
+            set_density(pane, |line, lines| match self.react {
+                React::CpuPerCore if !rest.is_empty() => rest[line * rest.len() / lines],
+                _ => level,
+            });
+        }
+    }
+}
+
+// How fast the rain falls for a level from 0.0 to 1.0
+pub fn speed(level: f32) -> u64 {
+    speed_millis(1 + (level.clamp(0.0, 1.0) * 9.0).round() as u8)
+}
+
+// Make each line in a pane as dense as its level, worked out from where the
+// line is and how many lines there are
+pub fn set_density(pane: &mut Pane, level: impl Fn(usize, usize) -> f32) {
+    for layer in pane.layers.iter_mut().filter(|layer| !layer.draining) {
+        let lines = layer.matrix.len();
+        for (i, line) in layer.matrix.iter_mut().enumerate() {
+            line.set_sparsity(layer.sparsity * sparsity(level(i, lines)));
+        }
+    }
+}
+
+// Busier means denser, down to the normal gaps at full load
+fn sparsity(level: f32) -> usize {
+    1 + ((1.0 - level.clamp(0.0, 1.0)) * (MAX_SPARSITY - 1.0)).round() as usize
+}
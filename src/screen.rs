@@ -0,0 +1,116 @@
+use crossterm::{
+    cursor::{Hide, Show},
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+use std::io::{stdout, Result};
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Once};
+
+static PANIC_HOOK: Once = Once::new();
+
+// Holds the terminal in raw mode on the alternate screen, and always gives it
+// back when dropped, whether main returns early with an error or unwinds
+pub struct Screen;
+
+impl Screen {
+    pub fn new() -> Result<Self> {
+        // The guard is only dropped after the panic message is printed, which
+        // would be lost on the alternate screen, so restore before printing
+        PANIC_HOOK.call_once(|| {
+            let hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                let _ = leave();
+                hook(info);
+            }));
+        });
+        enter()?;
+        Ok(Self)
+    }
+
+    // Give the terminal back to the shell and stop until resumed
+    pub fn suspend(&self, signals: &Signals) -> Result<()> {
+        leave()?;
+        signals.stop()?;
+        enter()
+    }
+
+    // Take the terminal back after being stopped and continued from outside
+    pub fn resume(&self) -> Result<()> {
+        enter()
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = leave();
+    }
+}
+
+fn enter() -> Result<()> {
+    stdout().execute(EnterAlternateScreen)?.execute(Hide)?;
+    enable_raw_mode()
+}
+
+fn leave() -> Result<()> {
+    disable_raw_mode()?;
+    stdout().execute(Show)?.execute(LeaveAlternateScreen)?;
+    Ok(())
+}
+
+// Signals from outside that the main loop checks on every tick. Raw mode keeps
+// the terminal from sending them for Ctrl+C and Ctrl+Z, but `kill` still can.
+#[derive(Default)]
+pub struct Signals {
+    quit: Arc<AtomicBool>,
+    suspend: Arc<AtomicBool>,
+    resume: Arc<AtomicBool>,
+}
+
+impl Signals {
+    #[cfg(unix)]
+    pub fn new() -> Result<Self> {
+        use signal_hook::consts::{SIGCONT, SIGHUP, SIGINT, SIGTERM, SIGTSTP};
+        use signal_hook::flag::register;
+
+        let signals = Self::default();
+        for signal in [SIGTERM, SIGHUP, SIGINT] {
+            register(signal, Arc::clone(&signals.quit))?;
+        }
+        register(SIGTSTP, Arc::clone(&signals.suspend))?;
+        register(SIGCONT, Arc::clone(&signals.resume))?;
+        Ok(signals)
+    }
+
+    #[cfg(not(unix))]
+    pub fn new() -> Result<Self> {
+        Ok(Self::default())
+    }
+
+    pub fn quit(&self) -> bool {
+        self.quit.load(Ordering::Relaxed)
+    }
+
+    pub fn take_suspend(&self) -> bool {
+        self.suspend.swap(false, Ordering::Relaxed)
+    }
+
+    pub fn take_resume(&self) -> bool {
+        self.resume.swap(false, Ordering::Relaxed)
+    }
+
+    // Stop the process the way SIGTSTP would have without our handler
+    #[cfg(unix)]
+    fn stop(&self) -> Result<()> {
+        signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+        // Already back on screen, so continuing doesn't need to do it again
+        self.resume.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn stop(&self) -> Result<()> {
+        Ok(())
+    }
+}
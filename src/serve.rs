@@ -0,0 +1,279 @@
+use crate::keys::{Action, Keymap};
+use crate::matrix::{other_colors, speed_millis, State};
+use crate::pane::Pane;
+use crate::telnet::{Input, Parser, HANDSHAKE};
+use crossterm::{
+    cursor::{Hide, Show},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+};
+use log::{debug, info, warn};
+use rand::{thread_rng, Rng};
+use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal, TerminalOptions, Viewport};
+use std::cell::Cell;
+use std::io::{ErrorKind, Read, Result, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// The size used until the client says how big its window is
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+// Keep a client from asking for an enormous screen
+const MAX_SIZE: (u16, u16) = (500, 200);
+const MAX_CLIENTS: usize = 64;
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+// How long to wait for the rest of an escape sequence after an ESC
+const ESCAPE_TIMEOUT: Duration = Duration::from_millis(100);
+
+// Accept clients until the process is killed, each with its own rain
+pub fn run(port: u16, bind: &str, state: State, layers: usize, keymap: Keymap) -> Result<()> {
+    let listener = TcpListener::bind((bind, port))?;
+    println!("Serving the matrix on {}", listener.local_addr()?);
+    let keymap = Arc::new(keymap);
+    let clients = Arc::new(AtomicUsize::new(0));
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                warn!("Failed to accept a client: {e}");
+                continue;
+            }
+        };
+        if clients.load(Ordering::Relaxed) >= MAX_CLIENTS {
+            let _ =
+                stream.write_all(b"Too many people are watching the matrix, try again later\r\n");
+            continue;
+        }
+        clients.fetch_add(1, Ordering::Relaxed);
+        let state = state.clone();
+        let keymap = Arc::clone(&keymap);
+        let clients = Arc::clone(&clients);
+        thread::spawn(move || {
+            let peer = stream
+                .peer_addr()
+                .map_or("unknown".to_string(), |addr| addr.to_string());
+            info!("{peer} connected");
+            if let Err(e) = serve_client(stream, state, layers, &keymap) {
+                debug!("{peer}: {e}");
+            }
+            info!("{peer} disconnected");
+            clients.fetch_sub(1, Ordering::Relaxed);
+        });
+    }
+    Ok(())
+}
+
+// Frames on their way to the thread writing them to the client
+#[derive(Default)]
+struct Outbox {
+    slot: Mutex<Slot>,
+    ready: Condvar,
+}
+
+#[derive(Default)]
+struct Slot {
+    // The next frame to write
+    frame: Option<Vec<u8>>,
+    // Whether a frame is being written right now
+    sending: bool,
+    // Stop once the last frame is written
+    closed: bool,
+}
+
+impl Outbox {
+    // Hand over a frame, unless the last one hasn't been written yet
+    fn try_send(&self, frame: impl FnOnce() -> Result<Vec<u8>>) -> Result<bool> {
+        let mut slot = self.slot.lock().unwrap();
+        if slot.frame.is_some() || slot.sending {
+            return Ok(false);
+        }
+        slot.frame = Some(frame()?);
+        self.ready.notify_one();
+        Ok(true)
+    }
+
+    // Send one last frame after everything before it is written
+    fn close(&self, frame: Vec<u8>) {
+        let mut slot = self.slot.lock().unwrap();
+        match slot.frame.as_mut() {
+            Some(pending) => pending.extend(frame),
+            None => slot.frame = Some(frame),
+        }
+        slot.closed = true;
+        self.ready.notify_one();
+    }
+}
+
+// Where the terminal writes each frame, so it can be taken and sent
+#[derive(Clone, Default)]
+struct Output(Rc<Cell<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let mut bytes = self.0.take();
+        bytes.extend_from_slice(buf);
+        self.0.set(bytes);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn serve_client(mut stream: TcpStream, state: State, layers: usize, keymap: &Keymap) -> Result<()> {
+    stream.set_nodelay(true)?;
+    // Give up on a client that stops reading altogether
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    stream.write_all(HANDSHAKE)?;
+    let inputs = read_inputs(stream.try_clone()?);
+    let outbox = Arc::new(Outbox::default());
+    let writer = write_frames(stream.try_clone()?, Arc::clone(&outbox));
+
+    let (width, height) = DEFAULT_SIZE;
+    let area = Rect::new(0, 0, width, height);
+    let output = Output::default();
+    let mut terminal = Terminal::with_options(
+        CrosstermBackend::new(output.clone()),
+        TerminalOptions {
+            viewport: Viewport::Fixed(area),
+        },
+    )?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen, Hide)?;
+    terminal.clear()?;
+    let mut pane = Pane::new(state, layers, area);
+    let mut help = false;
+    let mut dropping = false;
+
+    let result = loop {
+        let now = Instant::now();
+        if pane.is_due(now) {
+            pane.update(now);
+        }
+        // Skip drawing while the last frame is still being sent, so a slow
+        // client only misses frames instead of falling further behind
+        let sent = outbox.try_send(|| {
+            terminal.draw(|frame| {
+                pane.draw(frame);
+                if help {
+                    keymap.draw_help(frame);
+                }
+            })?;
+            Ok(output.0.take())
+        })?;
+        if !sent && !dropping {
+            debug!("Dropping frames for a slow client");
+        }
+        dropping = !sent;
+        if writer.is_finished() {
+            break Ok(());
+        }
+
+        let input = match inputs.recv_timeout(pane.next().saturating_duration_since(Instant::now()))
+        {
+            Ok(input) => input,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break Ok(()),
+        };
+        match input {
+            Input::Resize(width, height) => {
+                let area = Rect::new(
+                    0,
+                    0,
+                    width.clamp(1, MAX_SIZE.0),
+                    height.clamp(1, MAX_SIZE.1),
+                );
+                debug!("Client resized to {}x{}", area.width, area.height);
+                terminal.resize(area)?;
+                pane.resize(area);
+            }
+            Input::Key(key) => match keymap.action(&key) {
+                Some(Action::Quit) => break Ok(()),
+                Some(Action::Help) => help = !help,
+                Some(Action::ToggleBold) => pane.state.bold = !pane.state.bold,
+                Some(Action::CycleColor) => {
+                    let colors = other_colors(&pane.state);
+                    let index = thread_rng().gen_range(0..colors.len());
+                    pane.state.color = colors[index].to_string();
+                }
+                Some(Action::SetSpeed(speed)) => pane.state.speed = speed_millis(speed),
+                Some(Action::SetDirection(direction)) if pane.state.direction != direction => {
+                    pane.turn(direction);
+                }
+                // Suspending and moving between panes don't mean anything here
+                _ => {}
+            },
+        }
+    };
+
+    // Give the client's terminal back the way it was
+    let mut goodbye = Vec::new();
+    execute!(goodbye, Show, LeaveAlternateScreen)?;
+    outbox.close(goodbye);
+    let _ = writer.join();
+    let _ = stream.shutdown(Shutdown::Both);
+    result
+}
+
+// Read from the client on its own thread, sending on whatever it typed
+fn read_inputs(mut stream: TcpStream) -> Receiver<Input> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut parser = Parser::default();
+        let mut buf = [0; 1024];
+        let mut inputs = Vec::new();
+        loop {
+            // An escape sequence can be split across reads, so only wait so
+            // long for the rest before taking a lone ESC as the key
+            if stream
+                .set_read_timeout(parser.is_waiting().then_some(ESCAPE_TIMEOUT))
+                .is_err()
+            {
+                return;
+            }
+            match stream.read(&mut buf) {
+                Ok(0) => return,
+                Ok(read) => parser.parse(&buf[..read], &mut inputs),
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    parser.flush(&mut inputs);
+                }
+                Err(_) => return,
+            }
+            for input in inputs.drain(..) {
+                if sender.send(input).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    receiver
+}
+
+// Write each frame as it comes on its own thread, so a slow client never
+// holds up the rain
+fn write_frames(mut stream: TcpStream, outbox: Arc<Outbox>) -> thread::JoinHandle<()> {
+    thread::spawn(move || loop {
+        let (frame, closed) = {
+            let mut slot = outbox.slot.lock().unwrap();
+            while slot.frame.is_none() && !slot.closed {
+                slot = outbox.ready.wait(slot).unwrap();
+            }
+            slot.sending = true;
+            (slot.frame.take(), slot.closed)
+        };
+        if let Some(frame) = frame {
+            if stream.write_all(&frame).is_err() {
+                return;
+            }
+        }
+        if closed {
+            return;
+        }
+        outbox.slot.lock().unwrap().sending = false;
+    })
+}
@@ -0,0 +1,277 @@
+use crate::glyphs::{launch_glyphs, Symbol};
+use crate::matrix::{color_from_name, stream_color, LineState, State};
+use log::{info, warn};
+use rand::{thread_rng, Rng};
+use ratatui::style::Color;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::fs::{self, File, Metadata};
+use std::io::{Error, ErrorKind, Read, Result, Seek, SeekFrom};
+use std::path::PathBuf;
+
+// How many lines to hold on to while waiting for an idle stream
+const MAX_PENDING: usize = 1000;
+
+// Follows files for appended lines and launches each one as a stream
+pub struct Tail {
+    followers: Vec<Follower>,
+    highlights: Vec<(Regex, Color)>,
+    pending: VecDeque<(Vec<Symbol>, Option<Color>)>,
+}
+
+impl Tail {
+    // Highlights are given as `REGEX=COLOR`, e.g. `ERROR=red`
+    pub fn new(paths: &[String], highlights: &[String]) -> Result<Self> {
+        let followers = paths
+            .iter()
+            .map(|path| Follower::new(PathBuf::from(path)))
+            .collect::<Result<Vec<Follower>>>()?;
+        let highlights = highlights
+            .iter()
+            .map(|highlight| parse_highlight(highlight))
+            .collect::<Result<Vec<(Regex, Color)>>>()?;
+
+        Ok(Self {
+            followers,
+            highlights,
+            pending: VecDeque::new(),
+        })
+    }
+
+    // Read any new lines and launch as many as there are idle streams for
+    pub fn launch(&mut self, matrix: &mut [LineState], state: &State) -> Result<()> {
+        let mut lines = Vec::new();
+        for follower in self.followers.iter_mut() {
+            follower.poll(&mut lines)?;
+        }
+        for line in lines {
+            let color = self
+                .highlights
+                .iter()
+                .find(|(regex, _)| regex.is_match(&line))
+                .map(|(_, color)| *color);
+            // The line ending would otherwise end every stream in a blank head
+            let glyphs = launch_glyphs(line.trim_end_matches(['\r', '\n']), state.smooth);
+            if !glyphs.is_empty() {
+                self.pending.push_back((glyphs, color));
+            }
+        }
+        // Drop the oldest lines if they come in faster than they can fall
+        if self.pending.len() > MAX_PENDING {
+            warn!(
+                "Dropping {} lines that came in faster than they could fall",
+                self.pending.len() - MAX_PENDING
+            );
+        }
+        while self.pending.len() > MAX_PENDING {
+            self.pending.pop_front();
+        }
+
+        let mut idle: Vec<usize> = matrix
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.is_idle())
+            .map(|(i, _)| i)
+            .collect();
+        let mut rng = thread_rng();
+        while !idle.is_empty() {
+            let Some((glyphs, color)) = self.pending.pop_front() else {
+                break;
+            };
+            let i = idle.swap_remove(rng.gen_range(0..idle.len()));
+            matrix[i].launch(glyphs, color.or_else(|| stream_color(&state.color)));
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_highlight(highlight: &str) -> Result<(Regex, Color)> {
+    let invalid = |msg: String| Error::new(ErrorKind::InvalidInput, msg);
+    let (pattern, color) = highlight
+        .rsplit_once('=')
+        .ok_or_else(|| invalid(format!("highlight `{highlight}` should be REGEX=COLOR")))?;
+    let regex = Regex::new(pattern).map_err(|e| invalid(e.to_string()))?;
+    let color = color_from_name(&color.to_lowercase())
+        .ok_or_else(|| invalid(format!("unknown highlight color `{color}`")))?;
+    Ok((regex, color))
+}
+
+// A single followed file, which may be rotated or truncated underneath us
+struct Follower {
+    path: PathBuf,
+    file: Option<File>,
+    id: Option<u64>,
+    pos: u64,
+    partial: Vec<u8>,
+}
+
+impl Follower {
+    // Start at the end of the file so only new lines are shown
+    fn new(path: PathBuf) -> Result<Self> {
+        let mut follower = Self {
+            path,
+            file: None,
+            id: None,
+            pos: 0,
+            partial: Vec::new(),
+        };
+        if let Some(meta) = follower.metadata()? {
+            follower.open(&meta)?;
+            follower.pos = meta.len();
+        }
+        Ok(follower)
+    }
+
+    fn metadata(&self) -> Result<Option<Metadata>> {
+        match fs::metadata(&self.path) {
+            Ok(meta) => Ok(Some(meta)),
+            // The file is missing in the middle of a rotation
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn open(&mut self, meta: &Metadata) -> Result<()> {
+        self.file = Some(File::open(&self.path)?);
+        self.id = file_id(meta);
+        self.pos = 0;
+        self.partial.clear();
+        Ok(())
+    }
+
+    // Push every complete line appended since the last poll
+    fn poll(&mut self, lines: &mut Vec<String>) -> Result<()> {
+        let Some(meta) = self.metadata()? else {
+            return Ok(());
+        };
+        if self.file.is_none() {
+            self.open(&meta)?;
+        } else if file_id(&meta) != self.id {
+            // Finish what was written to the old file before it was rotated
+            info!("{} was rotated", self.path.display());
+            self.read(lines)?;
+            self.open(&meta)?;
+        } else if meta.len() < self.pos {
+            // Truncated, so start again from the top
+            info!("{} was truncated", self.path.display());
+            self.pos = 0;
+            self.partial.clear();
+        }
+        self.read(lines)
+    }
+
+    fn read(&mut self, lines: &mut Vec<String>) -> Result<()> {
+        let Some(file) = self.file.as_mut() else {
+            return Ok(());
+        };
+        file.seek(SeekFrom::Start(self.pos))?;
+        let read = file.read_to_end(&mut self.partial)?;
+        self.pos += read as u64;
+
+        while let Some(end) = self.partial.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.partial.drain(..=end).collect();
+            lines.push(String::from_utf8_lossy(&line).into_owned());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn file_id(meta: &Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(meta.ino())
+}
+
+#[cfg(not(unix))]
+fn file_id(_meta: &Metadata) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    // A file in the temp dir that is removed along with its rotated copy
+    // once the test is done
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, text: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("rjmatrix-{}-{name}", std::process::id()));
+            fs::write(&path, text).unwrap();
+            Self(path)
+        }
+
+        fn rotated(&self) -> PathBuf {
+            self.0.with_extension("1")
+        }
+
+        fn append(&self, path: &PathBuf, text: &str) {
+            let mut file = OpenOptions::new().append(true).open(path).unwrap();
+            file.write_all(text.as_bytes()).unwrap();
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+            let _ = fs::remove_file(self.rotated());
+        }
+    }
+
+    fn poll(follower: &mut Follower) -> Vec<String> {
+        let mut lines = Vec::new();
+        follower.poll(&mut lines).unwrap();
+        lines
+    }
+
+    #[test]
+    fn only_new_lines() {
+        let file = TempFile::new("new", "old\n");
+        let mut follower = Follower::new(file.0.clone()).unwrap();
+        assert_eq!(poll(&mut follower), Vec::<String>::new());
+        file.append(&file.0, "new\n");
+        assert_eq!(poll(&mut follower), ["new\n"]);
+    }
+
+    #[test]
+    fn partial_last_line() {
+        let file = TempFile::new("partial", "");
+        let mut follower = Follower::new(file.0.clone()).unwrap();
+        file.append(&file.0, "first\npar");
+        assert_eq!(poll(&mut follower), ["first\n"]);
+        assert_eq!(poll(&mut follower), Vec::<String>::new());
+        file.append(&file.0, "tial\n");
+        assert_eq!(poll(&mut follower), ["partial\n"]);
+    }
+
+    #[test]
+    fn truncated() {
+        let file = TempFile::new("truncated", "a long line\nand another\n");
+        let mut follower = Follower::new(file.0.clone()).unwrap();
+        fs::write(&file.0, "short\n").unwrap();
+        assert_eq!(poll(&mut follower), ["short\n"]);
+        file.append(&file.0, "more\n");
+        assert_eq!(poll(&mut follower), ["more\n"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rotated() {
+        let file = TempFile::new("rotated", "");
+        let mut follower = Follower::new(file.0.clone()).unwrap();
+        file.append(&file.0, "before\n");
+        fs::rename(&file.0, file.rotated()).unwrap();
+        // Written to the old file after it was moved, but before it's closed
+        file.append(&file.rotated(), "late\n");
+        // Missing for a moment while the new file is made
+        assert_eq!(poll(&mut follower), Vec::<String>::new());
+        fs::write(&file.0, "after\n").unwrap();
+        assert_eq!(poll(&mut follower), ["before\n", "late\n", "after\n"]);
+        file.append(&file.0, "next\n");
+        assert_eq!(poll(&mut follower), ["next\n"]);
+    }
+}
@@ -0,0 +1,394 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const ECHO: u8 = 1;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+const NAWS: u8 = 31;
+// Longest escape sequence or subnegotiation kept while waiting for its end,
+// so a client can't make the server hold on to everything it sends
+const MAX_SEQUENCE: usize = 32;
+const MAX_SUBNEGOTIATION: usize = 64;
+
+// Sent on connect so the client sends each key as it is pressed without
+// echoing it, and tells us its window size
+pub const HANDSHAKE: &[u8] = &[IAC, WILL, ECHO, IAC, WILL, SUPPRESS_GO_AHEAD, IAC, DO, NAWS];
+
+// Something the client sent, once the telnet commands are taken out
+#[derive(Clone, Debug, PartialEq)]
+pub enum Input {
+    Key(KeyEvent),
+    Resize(u16, u16),
+}
+
+#[derive(Default)]
+enum Mode {
+    #[default]
+    Data,
+    Command,
+    Option,
+    Subnegotiation,
+    SubnegotiationCommand,
+}
+
+// Splits what a client sends into key presses and window sizes. Commands and
+// escape sequences can be split across reads, so the parser keeps its place.
+#[derive(Default)]
+pub struct Parser {
+    mode: Mode,
+    subnegotiation: Vec<u8>,
+    data: Vec<u8>,
+}
+
+impl Parser {
+    pub fn parse(&mut self, bytes: &[u8], inputs: &mut Vec<Input>) {
+        for byte in bytes.iter().copied() {
+            self.mode = match (&self.mode, byte) {
+                (Mode::Data, IAC) => Mode::Command,
+                (Mode::Data, _) => {
+                    self.data.push(byte);
+                    Mode::Data
+                }
+                // A doubled IAC is a literal 255
+                (Mode::Command, IAC) => {
+                    self.data.push(byte);
+                    Mode::Data
+                }
+                (Mode::Command, SB) => {
+                    self.subnegotiation.clear();
+                    Mode::Subnegotiation
+                }
+                (Mode::Command, WILL | WONT | DO | DONT) => Mode::Option,
+                (Mode::Command | Mode::Option, _) => Mode::Data,
+                (Mode::Subnegotiation, IAC) => Mode::SubnegotiationCommand,
+                (Mode::SubnegotiationCommand, SE) => {
+                    if let [NAWS, w1, w2, h1, h2, ..] = self.subnegotiation[..] {
+                        let width = u16::from_be_bytes([w1, w2]);
+                        let height = u16::from_be_bytes([h1, h2]);
+                        inputs.push(Input::Resize(width, height));
+                    }
+                    Mode::Data
+                }
+                // Give up on a subnegotiation that doesn't end
+                (Mode::Subnegotiation | Mode::SubnegotiationCommand, _)
+                    if self.subnegotiation.len() >= MAX_SUBNEGOTIATION =>
+                {
+                    self.subnegotiation.clear();
+                    Mode::Data
+                }
+                (Mode::Subnegotiation, _) => {
+                    self.subnegotiation.push(byte);
+                    Mode::Subnegotiation
+                }
+                (Mode::SubnegotiationCommand, _) => {
+                    self.subnegotiation.push(byte);
+                    Mode::Subnegotiation
+                }
+            };
+        }
+        decode_keys(&mut self.data, inputs);
+    }
+
+    // Whether the last read ended partway through an escape sequence, which
+    // could also be a lone ESC
+    pub fn is_waiting(&self) -> bool {
+        self.data.first() == Some(&0x1b)
+    }
+
+    // Stop waiting for the rest of an escape sequence, taking a lone ESC as
+    // the key and dropping anything else
+    pub fn flush(&mut self, inputs: &mut Vec<Input>) {
+        if self.data == [0x1b] {
+            inputs.push(Input::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+        }
+        self.data.clear();
+    }
+}
+
+// Turn the bytes a terminal sends into keys, leaving any incomplete escape
+// sequence or character for the next read
+fn decode_keys(data: &mut Vec<u8>, inputs: &mut Vec<Input>) {
+    let mut used = 0;
+    while used < data.len() {
+        let Some((key, len)) = decode_key(&data[used..]) else {
+            break;
+        };
+        if let Some(key) = key {
+            inputs.push(Input::Key(key));
+        }
+        used += len;
+    }
+    data.drain(..used);
+}
+
+// The key at the start of the bytes and how many bytes it took, or `None` if
+// more bytes are needed
+fn decode_key(bytes: &[u8]) -> Option<(Option<KeyEvent>, usize)> {
+    let key = |code| Some(KeyEvent::new(code, KeyModifiers::NONE));
+    let ctrl = |c| Some(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL));
+    match bytes {
+        // A control sequence has parameters up to a final byte, like `1;5A`
+        // for Ctrl+Up or `15~` for F5, all of which are taken together
+        [0x1b, b'[', rest @ ..] => {
+            let Some(end) = rest.iter().position(|b| !(0x20..=0x3f).contains(b)) else {
+                // Drop a sequence that's gone on longer than any key's
+                return (bytes.len() >= MAX_SEQUENCE).then_some((None, bytes.len()));
+            };
+            let len = end + 3;
+            if !(0x40..=0x7e).contains(&rest[end]) {
+                // Not a sequence after all, so leave the byte that broke it
+                return Some((None, len - 1));
+            }
+            let code = match rest[end] {
+                b'A' => KeyCode::Up,
+                b'B' => KeyCode::Down,
+                b'C' => KeyCode::Right,
+                b'D' => KeyCode::Left,
+                b'Z' => KeyCode::BackTab,
+                _ => return Some((None, len)),
+            };
+            Some((Some(KeyEvent::new(code, csi_modifiers(&rest[..end]))), len))
+        }
+        [0x1b, b'O', code, ..] => {
+            let code = match code {
+                b'A' => KeyCode::Up,
+                b'B' => KeyCode::Down,
+                b'C' => KeyCode::Right,
+                b'D' => KeyCode::Left,
+                _ => return Some((None, 3)),
+            };
+            Some((key(code), 3))
+        }
+        // Wait for the rest of an escape sequence, or for `Parser::flush` to
+        // take a lone ESC as the key
+        [0x1b] | [0x1b, b'O'] => None,
+        [0x1b, ..] => Some((key(KeyCode::Esc), 1)),
+        // Enter is sent as CR LF or CR NUL
+        [b'\r', b'\n' | 0, ..] => Some((key(KeyCode::Enter), 2)),
+        [b'\r' | b'\n', ..] => Some((key(KeyCode::Enter), 1)),
+        [b'\t', ..] => Some((key(KeyCode::Tab), 1)),
+        [0x7f | 0x08, ..] => Some((key(KeyCode::Backspace), 1)),
+        [byte @ 0x01..=0x1a, ..] => Some((ctrl((byte - 1 + b'a') as char), 1)),
+        [0x00..=0x1f, ..] => Some((None, 1)),
+        _ => {
+            let len = match bytes[0] {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => 1,
+            };
+            if bytes.len() < len {
+                return None;
+            }
+            let c = std::str::from_utf8(&bytes[..len])
+                .ok()
+                .and_then(|text| text.chars().next());
+            Some((c.and_then(|c| key(KeyCode::Char(c))), len))
+        }
+    }
+}
+
+// The modifiers in the parameters of a control sequence, where the second
+// one is 1 plus 1 for Shift, 2 for Alt and 4 for Ctrl
+fn csi_modifiers(params: &[u8]) -> KeyModifiers {
+    let bits = std::str::from_utf8(params)
+        .ok()
+        .and_then(|params| params.split(';').nth(1))
+        .and_then(|modifiers| modifiers.parse::<u8>().ok())
+        .map_or(0, |modifiers| modifiers.saturating_sub(1));
+    let mut modifiers = KeyModifiers::NONE;
+    for (bit, modifier) in [
+        (1, KeyModifiers::SHIFT),
+        (2, KeyModifiers::ALT),
+        (4, KeyModifiers::CONTROL),
+    ] {
+        if bits & bit != 0 {
+            modifiers |= modifier;
+        }
+    }
+    modifiers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(parser: &mut Parser, bytes: &[u8]) -> Vec<Input> {
+        let mut inputs = Vec::new();
+        parser.parse(bytes, &mut inputs);
+        inputs
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Input {
+        Input::Key(KeyEvent::new(code, modifiers))
+    }
+
+    fn typed(c: char) -> Input {
+        key(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn naws_split_across_reads() {
+        let mut parser = Parser::default();
+        assert_eq!(parse(&mut parser, &[IAC, SB, NAWS, 0, 120]), vec![]);
+        assert_eq!(parse(&mut parser, &[0, 40, IAC]), vec![]);
+        assert_eq!(
+            parse(&mut parser, &[SE, b'q']),
+            vec![Input::Resize(120, 40), typed('q')]
+        );
+    }
+
+    #[test]
+    fn naws_with_doubled_iac() {
+        let mut parser = Parser::default();
+        assert_eq!(
+            parse(&mut parser, &[IAC, SB, NAWS, 0, IAC, IAC, 0, 50, IAC, SE]),
+            vec![Input::Resize(255, 50)]
+        );
+    }
+
+    #[test]
+    fn doubled_iac_is_data() {
+        let mut parser = Parser::default();
+        // A literal 255 isn't valid UTF-8 so it's no key, but it mustn't
+        // start a command that eats the next one, even split across reads
+        assert_eq!(parse(&mut parser, &[IAC, IAC, b'a']), vec![typed('a')]);
+        assert_eq!(parse(&mut parser, &[IAC]), vec![]);
+        assert_eq!(parse(&mut parser, &[IAC, b'b']), vec![typed('b')]);
+    }
+
+    #[test]
+    fn options_are_skipped() {
+        let mut parser = Parser::default();
+        assert_eq!(
+            parse(&mut parser, &[IAC, DO, ECHO, b'x', IAC, WONT]),
+            vec![typed('x')]
+        );
+        assert_eq!(parse(&mut parser, &[NAWS, b'y']), vec![typed('y')]);
+    }
+
+    #[test]
+    fn split_escape_sequence() {
+        let mut parser = Parser::default();
+        assert_eq!(parse(&mut parser, b"\x1b"), vec![]);
+        assert!(parser.is_waiting());
+        assert_eq!(
+            parse(&mut parser, b"[A"),
+            vec![key(KeyCode::Up, KeyModifiers::NONE)]
+        );
+        assert!(!parser.is_waiting());
+
+        assert_eq!(parse(&mut parser, b"\x1b["), vec![]);
+        assert_eq!(parse(&mut parser, b"1;"), vec![]);
+        assert_eq!(
+            parse(&mut parser, b"2D"),
+            vec![key(KeyCode::Left, KeyModifiers::SHIFT)]
+        );
+    }
+
+    #[test]
+    fn lone_escape_after_timeout() {
+        let mut parser = Parser::default();
+        assert_eq!(parse(&mut parser, b"\x1b"), vec![]);
+        let mut inputs = Vec::new();
+        parser.flush(&mut inputs);
+        assert_eq!(inputs, vec![key(KeyCode::Esc, KeyModifiers::NONE)]);
+        assert!(!parser.is_waiting());
+        assert_eq!(parse(&mut parser, b"q"), vec![typed('q')]);
+    }
+
+    #[test]
+    fn escape_before_other_keys() {
+        let mut parser = Parser::default();
+        assert_eq!(
+            parse(&mut parser, b"\x1bq"),
+            vec![key(KeyCode::Esc, KeyModifiers::NONE), typed('q')]
+        );
+    }
+
+    #[test]
+    fn control_sequences_with_parameters() {
+        let mut parser = Parser::default();
+        assert_eq!(
+            parse(&mut parser, b"\x1b[1;5A"),
+            vec![key(KeyCode::Up, KeyModifiers::CONTROL)]
+        );
+        // F5 and the like are dropped whole, without leaking any digits
+        assert_eq!(parse(&mut parser, b"\x1b[15~\x1b[5;3~x"), vec![typed('x')]);
+        assert_eq!(
+            parse(&mut parser, b"\x1b[Z\x1bOB"),
+            vec![
+                key(KeyCode::BackTab, KeyModifiers::NONE),
+                key(KeyCode::Down, KeyModifiers::NONE)
+            ]
+        );
+        // F1 sent as SS3
+        assert_eq!(parse(&mut parser, b"\x1bOPy"), vec![typed('y')]);
+    }
+
+    #[test]
+    fn broken_control_sequence() {
+        let mut parser = Parser::default();
+        assert_eq!(
+            parse(&mut parser, b"\x1b[1\r"),
+            vec![key(KeyCode::Enter, KeyModifiers::NONE)]
+        );
+    }
+
+    #[test]
+    fn overlong_control_sequence() {
+        let mut parser = Parser::default();
+        assert_eq!(
+            parse(&mut parser, b"\x1b[1;1;1;1;1;1;1;1;1;1;1;1;1;1;1"),
+            vec![]
+        );
+        assert!(parser.is_waiting());
+        parse(&mut parser, b";1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1");
+        assert!(parser.data.len() < MAX_SEQUENCE);
+        assert!(!parser.is_waiting());
+        for _ in 0..1000 {
+            parse(
+                &mut parser,
+                b"\x1b[1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1",
+            );
+            assert!(parser.data.len() < MAX_SEQUENCE);
+        }
+    }
+
+    #[test]
+    fn unterminated_subnegotiation() {
+        let mut parser = Parser::default();
+        parse(&mut parser, &[IAC, SB, NAWS]);
+        for _ in 0..1000 {
+            parse(&mut parser, &[0; 100]);
+            assert!(parser.subnegotiation.len() <= MAX_SUBNEGOTIATION);
+        }
+        assert!(matches!(parser.mode, Mode::Data));
+        assert_eq!(parse(&mut parser, b"q"), vec![typed('q')]);
+    }
+
+    #[test]
+    fn enter_and_control_keys() {
+        let mut parser = Parser::default();
+        assert_eq!(
+            parse(&mut parser, b"\r\0\x03"),
+            vec![
+                key(KeyCode::Enter, KeyModifiers::NONE),
+                key(KeyCode::Char('c'), KeyModifiers::CONTROL)
+            ]
+        );
+    }
+
+    #[test]
+    fn utf8_split_across_reads() {
+        let mut parser = Parser::default();
+        let bytes = "é".as_bytes();
+        assert_eq!(parse(&mut parser, &bytes[..1]), vec![]);
+        assert_eq!(parse(&mut parser, &bytes[1..]), vec![typed('é')]);
+    }
+}